@@ -0,0 +1,240 @@
+//! Derive macros for the `mayhem` crate.
+
+#![warn(missing_debug_implementations, missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+	ext::IdentExt, parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, LitInt,
+	LitStr, Result,
+};
+
+/// Derive a typed view over a node.
+///
+/// Generates `TryFrom<NodeWalker>` and `TryFrom<&NodeWalker>` implementations,
+/// as well as `FromField` such that the type can be used as a field of other
+/// derived types. Each struct field is converted from the node field of the
+/// same name via `FromField`.
+///
+/// Container attributes:
+/// - `#[mayhem(class = "hkaSkeleton")]`: Require the node to be, or inherit from, the named class.
+/// - `#[mayhem(min_version = 5, max_version = 5)]`: Require the named class to be within a version range. Requires `class`.
+/// - `#[mayhem(rename_all = "camelCase")]`: Convert struct field names to camel case when looking up node fields.
+///
+/// Field attributes:
+/// - `#[mayhem(rename = "referencePose")]`: Look up the node field with the specified name.
+/// - `#[mayhem(default)]`: Use `Default::default()` if the node field does not exist.
+/// - `#[mayhem(flatten)]`: Convert the field from the node itself, rather than one of its fields. Useful for composing the fields of a parent class.
+#[proc_macro_derive(FromNode, attributes(mayhem))]
+pub fn derive_from_node(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(input)
+		.unwrap_or_else(Error::into_compile_error)
+		.into()
+}
+
+#[derive(Default)]
+struct ContainerOptions {
+	class: Option<LitStr>,
+	min_version: Option<LitInt>,
+	max_version: Option<LitInt>,
+	camel_case: bool,
+}
+
+#[derive(Default)]
+struct FieldOptions {
+	rename: Option<LitStr>,
+	default: bool,
+	flatten: bool,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+	let options = container_options(&input)?;
+
+	let fields = match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => &fields.named,
+			_ => {
+				return Err(Error::new(
+					input.span(),
+					"FromNode can only be derived for structs with named fields",
+				))
+			}
+		},
+		_ => {
+			return Err(Error::new(
+				input.span(),
+				"FromNode can only be derived for structs",
+			))
+		}
+	};
+
+	let field_values = fields
+		.iter()
+		.map(|field| {
+			let field_options = field_options(field)?;
+			let ident = field.ident.as_ref().unwrap();
+			if field_options.flatten {
+				return Ok(quote! {
+					#ident: ::mayhem::__private::flatten(node)?
+				});
+			}
+
+			let name = match field_options.rename {
+				Some(rename) => rename.value(),
+				None => {
+					let name = ident.unraw().to_string();
+					match options.camel_case {
+						true => camel_case(&name),
+						false => name,
+					}
+				}
+			};
+
+			let function = match field_options.default {
+				true => quote!(field_or_default),
+				false => quote!(field),
+			};
+
+			Ok(quote! {
+				#ident: ::mayhem::__private::#function(node, #name)?
+			})
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	let check_class = match &options.class {
+		Some(class) => {
+			let min_version = option_tokens(&options.min_version);
+			let max_version = option_tokens(&options.max_version);
+			quote! {
+				::mayhem::__private::check_class(node, #class, #min_version, #max_version)?;
+			}
+		}
+		None => {
+			if let Some(version) = options
+				.min_version
+				.as_ref()
+				.or(options.max_version.as_ref())
+			{
+				return Err(Error::new(
+					version.span(),
+					"version constraints require a class",
+				));
+			}
+			quote!()
+		}
+	};
+
+	let ident = &input.ident;
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::core::convert::TryFrom<&::mayhem::NodeWalker> for #ident #type_generics #where_clause {
+			type Error = ::mayhem::Error;
+
+			fn try_from(node: &::mayhem::NodeWalker) -> ::core::result::Result<Self, Self::Error> {
+				#check_class
+				::core::result::Result::Ok(Self {
+					#(#field_values,)*
+				})
+			}
+		}
+
+		impl #impl_generics ::core::convert::TryFrom<::mayhem::NodeWalker> for #ident #type_generics #where_clause {
+			type Error = ::mayhem::Error;
+
+			fn try_from(node: ::mayhem::NodeWalker) -> ::core::result::Result<Self, Self::Error> {
+				Self::try_from(&node)
+			}
+		}
+
+		impl #impl_generics ::mayhem::FromField for #ident #type_generics #where_clause {
+			fn from_field(
+				node: &::mayhem::NodeWalker,
+				value: &::mayhem::Value,
+			) -> ::core::result::Result<Self, ::mayhem::Error> {
+				::mayhem::__private::from_reference(node, value)
+			}
+		}
+	})
+}
+
+fn container_options(input: &DeriveInput) -> Result<ContainerOptions> {
+	let mut options = ContainerOptions::default();
+
+	for attribute in input
+		.attrs
+		.iter()
+		.filter(|attr| attr.path().is_ident("mayhem"))
+	{
+		attribute.parse_nested_meta(|meta| {
+			if meta.path.is_ident("class") {
+				options.class = Some(meta.value()?.parse()?);
+			} else if meta.path.is_ident("min_version") {
+				options.min_version = Some(meta.value()?.parse()?);
+			} else if meta.path.is_ident("max_version") {
+				options.max_version = Some(meta.value()?.parse()?);
+			} else if meta.path.is_ident("rename_all") {
+				let rule = meta.value()?.parse::<LitStr>()?;
+				if rule.value() != "camelCase" {
+					return Err(Error::new(rule.span(), "only \"camelCase\" is supported"));
+				}
+				options.camel_case = true;
+			} else {
+				return Err(meta.error("unknown mayhem container attribute"));
+			}
+			Ok(())
+		})?;
+	}
+
+	Ok(options)
+}
+
+fn field_options(field: &syn::Field) -> Result<FieldOptions> {
+	let mut options = FieldOptions::default();
+
+	for attribute in field
+		.attrs
+		.iter()
+		.filter(|attr| attr.path().is_ident("mayhem"))
+	{
+		attribute.parse_nested_meta(|meta| {
+			if meta.path.is_ident("rename") {
+				options.rename = Some(meta.value()?.parse()?);
+			} else if meta.path.is_ident("default") {
+				options.default = true;
+			} else if meta.path.is_ident("flatten") {
+				options.flatten = true;
+			} else {
+				return Err(meta.error("unknown mayhem field attribute"));
+			}
+			Ok(())
+		})?;
+	}
+
+	Ok(options)
+}
+
+fn option_tokens(value: &Option<LitInt>) -> TokenStream2 {
+	match value {
+		Some(value) => quote!(::core::option::Option::Some(#value)),
+		None => quote!(::core::option::Option::None),
+	}
+}
+
+fn camel_case(name: &str) -> String {
+	let mut output = String::with_capacity(name.len());
+	let mut upper = false;
+	for character in name.trim_start_matches('_').chars() {
+		match character {
+			'_' => upper = true,
+			character if upper => {
+				output.extend(character.to_uppercase());
+				upper = false;
+			}
+			character => output.push(character),
+		}
+	}
+	output
+}
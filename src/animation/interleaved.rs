@@ -0,0 +1,249 @@
+use crate::{
+	error::{Error, Result},
+	from_node::{
+		__private::{check_class, from_reference},
+		FromField,
+	},
+	math::QsTransform,
+	value::Value,
+	walker::NodeWalker,
+};
+
+use super::Animation;
+
+/// Typed view of an `hkaInterleavedUncompressedAnimation`.
+///
+/// Every frame stores a value for each track, ordered by frame and then by
+/// track. Sampling between frames interpolates the neighbouring frames.
+#[derive(Clone, Debug)]
+pub struct InterleavedAnimation {
+	duration: f32,
+	frame_count: usize,
+	transform_track_count: usize,
+	float_track_count: usize,
+	transforms: Vec<QsTransform>,
+	floats: Vec<f32>,
+}
+
+impl InterleavedAnimation {
+	/// Get the transforms of every track at a single frame.
+	pub fn frame_transforms(&self, frame: usize) -> Option<&[QsTransform]> {
+		let start = frame.checked_mul(self.transform_track_count)?;
+		self.transforms
+			.get(start..start + self.transform_track_count)
+	}
+
+	/// Get the values of every float track at a single frame.
+	pub fn frame_floats(&self, frame: usize) -> Option<&[f32]> {
+		let start = frame.checked_mul(self.float_track_count)?;
+		self.floats.get(start..start + self.float_track_count)
+	}
+
+	/// Find the frames either side of a time, and the position between them.
+	fn locate(&self, time: f32) -> (usize, usize, f32) {
+		let last = self.frame_count.saturating_sub(1);
+		if last == 0 || self.duration <= 0. {
+			return (0, 0, 0.);
+		}
+
+		let frame = time.clamp(0., self.duration) / self.duration * last as f32;
+		let before = (frame as usize).min(last);
+		let after = (before + 1).min(last);
+		(before, after, frame - before as f32)
+	}
+}
+
+impl Animation for InterleavedAnimation {
+	fn duration(&self) -> f32 {
+		self.duration
+	}
+
+	fn frame_count(&self) -> usize {
+		self.frame_count
+	}
+
+	fn transform_track_count(&self) -> usize {
+		self.transform_track_count
+	}
+
+	fn float_track_count(&self) -> usize {
+		self.float_track_count
+	}
+
+	fn sample_transform(&self, track: usize, time: f32) -> Option<QsTransform> {
+		if track >= self.transform_track_count {
+			return None;
+		}
+
+		let (before, after, t) = self.locate(time);
+		let before = self.frame_transforms(before)?[track];
+		let after = self.frame_transforms(after)?[track];
+		Some(before.interpolate(after, t))
+	}
+
+	fn sample_float(&self, track: usize, time: f32) -> Option<f32> {
+		if track >= self.float_track_count {
+			return None;
+		}
+
+		let (before, after, t) = self.locate(time);
+		let before = self.frame_floats(before)?[track];
+		let after = self.frame_floats(after)?[track];
+		Some(before + (after - before) * t)
+	}
+}
+
+/// Get the number of frames stored for a set of tracks, checking that every
+/// frame is complete.
+fn frame_count(name: &str, length: usize, track_count: usize) -> Result<Option<usize>> {
+	if track_count == 0 {
+		return Ok(None);
+	}
+	if !length.is_multiple_of(track_count) {
+		return Err(Error::Invalid(format!(
+			"Expected a multiple of {track_count} {name}, got {length}."
+		)));
+	}
+	Ok(Some(length / track_count))
+}
+
+impl TryFrom<&NodeWalker> for InterleavedAnimation {
+	type Error = Error;
+
+	fn try_from(node: &NodeWalker) -> Result<Self> {
+		check_class(node, "hkaInterleavedUncompressedAnimation", None, None)?;
+
+		let duration = node.get::<f32>("duration")?;
+		if !(duration.is_finite() && duration >= 0.) {
+			return Err(Error::Invalid(format!("Invalid duration {duration}.")));
+		}
+
+		let transform_track_count = node.get::<usize>("numberOfTransformTracks")?;
+		let float_track_count = node.get::<usize>("numberOfFloatTracks")?;
+		let transforms = node.get::<Vec<QsTransform>>("transforms")?;
+		let floats = node.get::<Vec<f32>>("floats")?;
+
+		let transform_frames = frame_count("transforms", transforms.len(), transform_track_count)?;
+		let float_frames = frame_count("floats", floats.len(), float_track_count)?;
+		let frame_count = match (transform_frames, float_frames) {
+			(Some(transforms), Some(floats)) if transforms != floats => {
+				return Err(Error::Invalid(format!(
+					"Transforms store {transforms} frames, but floats store {floats}."
+				)))
+			}
+			(Some(frames), _) | (None, Some(frames)) => frames,
+			(None, None) => 0,
+		};
+
+		Ok(Self {
+			duration,
+			frame_count,
+			transform_track_count,
+			float_track_count,
+			transforms,
+			floats,
+		})
+	}
+}
+
+impl TryFrom<NodeWalker> for InterleavedAnimation {
+	type Error = Error;
+
+	fn try_from(node: NodeWalker) -> Result<Self> {
+		Self::try_from(&node)
+	}
+}
+
+impl FromField for InterleavedAnimation {
+	fn from_field(node: &NodeWalker, value: &Value) -> Result<Self> {
+		from_reference(node, value)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		animation::Animation,
+		node::FieldKind,
+		testing::{definition, node, transform, walker},
+		value::Value,
+	};
+
+	use super::InterleavedAnimation;
+
+	#[test]
+	fn interleaved() {
+		let animation = definition(
+			"hkaAnimation",
+			None,
+			vec![
+				("duration", FieldKind::Float),
+				("numberOfTransformTracks", FieldKind::Integer),
+				("numberOfFloatTracks", FieldKind::Integer),
+			],
+		);
+		let interleaved = definition(
+			"hkaInterleavedUncompressedAnimation",
+			Some(&animation),
+			vec![
+				(
+					"transforms",
+					FieldKind::Vector(FieldKind::Array(FieldKind::Float.into(), 12).into()),
+				),
+				("floats", FieldKind::Vector(FieldKind::Float.into())),
+			],
+		);
+
+		// Three frames of two transform tracks and one float track.
+		let walker = |duration| {
+			walker(vec![node(
+				&interleaved,
+				vec![
+					Some(Value::F32(duration)),
+					Some(Value::I32(2)),
+					Some(Value::I32(1)),
+					Some(Value::Vector(
+						[0., 10., 1., 20., 2., 30.]
+							.into_iter()
+							.map(transform)
+							.collect(),
+					)),
+					Some(Value::Vector(
+						[0., 1., 4.].into_iter().map(Value::F32).collect(),
+					)),
+				],
+			)])
+		};
+		for duration in [f32::NAN, f32::INFINITY, -1.] {
+			assert_eq!(
+				InterleavedAnimation::try_from(walker(duration))
+					.unwrap_err()
+					.to_string(),
+				format!("Invalid: Invalid duration {duration}.")
+			);
+		}
+
+		let walker = walker(2.);
+		let animation = InterleavedAnimation::try_from(&walker).unwrap();
+		assert_eq!(animation.frame_count(), 3);
+		assert_eq!(animation.frame_transforms(1).unwrap()[1].translation.x, 20.);
+		assert!(animation.frame_transforms(3).is_none());
+		assert_eq!(animation.frame_floats(2), Some(&[4.][..]));
+
+		let transforms = animation.sample_transforms(1.5);
+		assert_eq!(transforms.len(), 2);
+		assert_eq!(transforms[0].translation.x, 1.5);
+		assert_eq!(transforms[1].translation.x, 25.);
+		assert_eq!(animation.sample_floats(1.5), [2.5]);
+
+		assert_eq!(
+			animation.sample_transform(1, 10.).unwrap().translation.x,
+			30.
+		);
+		assert_eq!(animation.sample_float(0, -1.), Some(0.));
+		assert!(animation.sample_transform(2, 0.).is_none());
+
+		let animation = crate::animation::read(&walker).unwrap();
+		assert_eq!(animation.sample_floats(1.5), [2.5]);
+	}
+}
@@ -0,0 +1,76 @@
+//! Typed views of Havok animation classes.
+//!
+//! Each type is built from a [`NodeWalker`] of the
+//! corresponding class, reading the fields of every class version seen in
+//! game data.
+
+mod interleaved;
+mod pose;
+mod skeleton;
+mod spline;
+
+pub use {
+	interleaved::InterleavedAnimation,
+	skeleton::{Bone, LocalFrame, Partition, Skeleton},
+	spline::SplineCompressedAnimation,
+};
+
+use crate::{
+	error::{Error, Result},
+	math::QsTransform,
+	walker::NodeWalker,
+};
+
+/// Read an `hkaAnimation` of any supported storage format.
+pub fn read(node: &NodeWalker) -> Result<Box<dyn Animation + Send + Sync>> {
+	if node.is_a("hkaSplineCompressedAnimation") {
+		Ok(Box::new(SplineCompressedAnimation::try_from(node)?))
+	} else if node.is_a("hkaInterleavedUncompressedAnimation") {
+		Ok(Box::new(InterleavedAnimation::try_from(node)?))
+	} else {
+		Err(Error::Invalid(format!(
+			"Unsupported animation class {}.",
+			node.name()
+		)))
+	}
+}
+
+/// Sampling of the tracks of an animation, independent of how the animation
+/// is stored.
+///
+/// Times are in seconds, and are clamped to the duration of the animation.
+/// Transforms are relative to the parent of the bone each track is bound to.
+pub trait Animation {
+	/// Get the duration of the animation, in seconds.
+	fn duration(&self) -> f32;
+
+	/// Get the number of frames stored by the animation.
+	fn frame_count(&self) -> usize;
+
+	/// Get the number of transform tracks.
+	fn transform_track_count(&self) -> usize;
+
+	/// Get the number of float tracks.
+	fn float_track_count(&self) -> usize;
+
+	/// Sample a single transform track. Returns `None` if the track does not
+	/// exist.
+	fn sample_transform(&self, track: usize, time: f32) -> Option<QsTransform>;
+
+	/// Sample a single float track. Returns `None` if the track does not exist.
+	fn sample_float(&self, track: usize, time: f32) -> Option<f32>;
+
+	/// Sample every transform track.
+	fn sample_transforms(&self, time: f32) -> Vec<QsTransform> {
+		(0..self.transform_track_count())
+			.filter_map(|track| self.sample_transform(track, time))
+			.collect()
+	}
+
+	/// Sample every float track.
+	fn sample_floats(&self, time: f32) -> Vec<f32> {
+		(0..self.float_track_count())
+			.filter_map(|track| self.sample_float(track, time))
+			.collect()
+	}
+}
@@ -0,0 +1,242 @@
+use crate::{
+	error::{Error, Result},
+	math::{Matrix4, QsTransform},
+};
+
+use super::Skeleton;
+
+/// Conversion of poses between the local space of each bone, relative to its
+/// parent, and model space, relative to the origin of the skeleton.
+///
+/// Root bones are relative to the model origin, such that skeletons with
+/// multiple roots are supported. Each bone must appear after its parent, as
+/// required by Havok; this is validated before every conversion.
+impl Skeleton {
+	/// Check that every bone appears after its parent, such that poses can be
+	/// converted in a single pass over the bones.
+	pub fn validate_parent_order(&self) -> Result<()> {
+		for (index, bone) in self.bones().iter().enumerate() {
+			if let Some(parent) = bone.parent.filter(|parent| *parent >= index) {
+				return Err(Error::Invalid(format!(
+					"Bone {index} ({}) appears before its parent {parent}.",
+					bone.name
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	/// Convert a local pose to model space, composing transforms directly.
+	/// This is approximate where non-uniform scale is combined with rotation;
+	/// use [`model_matrices`](Self::model_matrices) for an exact result.
+	pub fn model_transforms(&self, local: &[QsTransform]) -> Result<Vec<QsTransform>> {
+		self.to_model(local.to_vec(), |parent, local| parent * local)
+	}
+
+	/// Convert a local pose to model space matrices.
+	pub fn model_matrices(&self, local: &[QsTransform]) -> Result<Vec<Matrix4>> {
+		let local = local
+			.iter()
+			.map(|transform| transform.to_matrix())
+			.collect();
+		self.to_model(local, |parent, local| parent * local)
+	}
+
+	/// Convert a local pose to world space matrices, with the model origin
+	/// placed by the provided matrix.
+	pub fn world_matrices(&self, local: &[QsTransform], world: Matrix4) -> Result<Vec<Matrix4>> {
+		Ok(self
+			.model_matrices(local)?
+			.into_iter()
+			.map(|model| world * model)
+			.collect())
+	}
+
+	/// Get the matrices that transform from model space to the space of each
+	/// bone in the reference pose, as used for skinning.
+	pub fn inverse_bind_matrices(&self) -> Result<Vec<Matrix4>> {
+		self.model_matrices(&self.reference_pose())?
+			.into_iter()
+			.enumerate()
+			.map(|(index, model)| {
+				model.inverse().ok_or_else(|| {
+					Error::Invalid(format!("Bone {index} has a singular reference pose."))
+				})
+			})
+			.collect()
+	}
+
+	/// Convert a model space pose back to local space, composing transforms
+	/// directly.
+	pub fn local_transforms(&self, model: &[QsTransform]) -> Result<Vec<QsTransform>> {
+		self.to_local(model, |parent, model| Ok(parent.inverse() * model))
+	}
+
+	/// Convert model space matrices back to local space.
+	pub fn local_matrices(&self, model: &[Matrix4]) -> Result<Vec<Matrix4>> {
+		self.to_local(model, |parent, model| {
+			parent
+				.inverse()
+				.map(|inverse| inverse * model)
+				.ok_or_else(|| Error::Invalid("Singular parent matrix.".into()))
+		})
+	}
+
+	fn check_pose_length(&self, length: usize) -> Result<()> {
+		self.validate_parent_order()?;
+		let bone_count = self.bones().len();
+		if length != bone_count {
+			return Err(Error::Invalid(format!(
+				"Expected a pose of {bone_count} bones, got {length}."
+			)));
+		}
+		Ok(())
+	}
+
+	fn to_model<T: Copy>(&self, mut pose: Vec<T>, compose: impl Fn(T, T) -> T) -> Result<Vec<T>> {
+		self.check_pose_length(pose.len())?;
+
+		// Parents are visited before their children, so each parent is already
+		// in model space when it is composed.
+		for (index, bone) in self.bones().iter().enumerate() {
+			if let Some(parent) = bone.parent {
+				pose[index] = compose(pose[parent], pose[index]);
+			}
+		}
+		Ok(pose)
+	}
+
+	fn to_local<T: Copy>(
+		&self,
+		model: &[T],
+		relative: impl Fn(T, T) -> Result<T>,
+	) -> Result<Vec<T>> {
+		self.check_pose_length(model.len())?;
+
+		self.bones()
+			.iter()
+			.zip(model)
+			.enumerate()
+			.map(|(index, (bone, model_transform))| match bone.parent {
+				Some(parent) => relative(model[parent], *model_transform)
+					.map_err(|error| Error::Invalid(format!("Bone {index}: {error}"))),
+				None => Ok(*model_transform),
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::f32::consts::FRAC_PI_2;
+
+	use crate::{
+		animation::{Bone, Skeleton},
+		math::{Matrix4, QsTransform, Quaternion, Vector4},
+		testing::{assert_matrix_near, assert_near, EPSILON},
+	};
+
+	fn bone(parent: Option<usize>, x: f32) -> Bone {
+		Bone {
+			name: String::new(),
+			parent,
+			lock_translation: false,
+			reference_pose: QsTransform::new(
+				Vector4::new(x, 0., 0., 0.),
+				Quaternion::from_axis_angle(Vector4::new(0., 0., 1., 0.), FRAC_PI_2),
+				Vector4::ONE,
+			),
+		}
+	}
+
+	#[test]
+	fn model_space() {
+		// Two chains, rooted at bones 0 and 2.
+		let skeleton = Skeleton::new(
+			"",
+			vec![
+				bone(None, 1.),
+				bone(Some(0), 2.),
+				bone(None, 5.),
+				bone(Some(1), 3.),
+			],
+		)
+		.unwrap();
+		let local = skeleton.reference_pose();
+
+		let model = skeleton.model_matrices(&local).unwrap();
+		let origin = Vector4::new(0., 0., 0., 1.);
+		assert_near(
+			model[0].transform_point(origin),
+			Vector4::new(1., 0., 0., 1.),
+			EPSILON,
+		);
+		assert_near(
+			model[1].transform_point(origin),
+			Vector4::new(1., 2., 0., 1.),
+			EPSILON,
+		);
+		assert_near(
+			model[2].transform_point(origin),
+			Vector4::new(5., 0., 0., 1.),
+			EPSILON,
+		);
+		assert_near(
+			model[3].transform_point(origin),
+			Vector4::new(-2., 2., 0., 1.),
+			EPSILON,
+		);
+
+		let transforms = skeleton.model_transforms(&local).unwrap();
+		for (transform, matrix) in transforms.iter().zip(&model) {
+			assert_matrix_near(transform.to_matrix(), *matrix, EPSILON);
+		}
+
+		let world = QsTransform::new(
+			Vector4::new(0., 0., 10., 0.),
+			Quaternion::IDENTITY,
+			Vector4::ONE,
+		)
+		.to_matrix();
+		let world_matrices = skeleton.world_matrices(&local, world).unwrap();
+		assert_near(
+			world_matrices[3].transform_point(origin),
+			Vector4::new(-2., 2., 10., 1.),
+			EPSILON,
+		);
+
+		for (inverse, model) in skeleton
+			.inverse_bind_matrices()
+			.unwrap()
+			.into_iter()
+			.zip(&model)
+		{
+			assert_matrix_near(inverse * *model, Matrix4::IDENTITY, EPSILON);
+		}
+
+		let back = skeleton.local_matrices(&model).unwrap();
+		for (back, local) in back.into_iter().zip(&local) {
+			assert_matrix_near(back, local.to_matrix(), EPSILON);
+		}
+		let back = skeleton.local_transforms(&transforms).unwrap();
+		for (back, local) in back.into_iter().zip(&local) {
+			assert_matrix_near(back.to_matrix(), local.to_matrix(), EPSILON);
+		}
+	}
+
+	#[test]
+	fn validation() {
+		let skeleton = Skeleton::new("", vec![bone(Some(1), 0.), bone(None, 0.)]).unwrap();
+		assert_eq!(
+			skeleton.validate_parent_order().unwrap_err().to_string(),
+			"Invalid: Bone 0 () appears before its parent 1."
+		);
+		assert!(skeleton.model_matrices(&skeleton.reference_pose()).is_err());
+
+		let skeleton = Skeleton::new("", vec![bone(None, 0.)]).unwrap();
+		assert_eq!(
+			skeleton.model_transforms(&[]).unwrap_err().to_string(),
+			"Invalid: Expected a pose of 1 bones, got 0."
+		);
+	}
+}
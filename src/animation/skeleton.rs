@@ -0,0 +1,470 @@
+use crate::{
+	error::{Error, Result},
+	from_node::{
+		__private::{check_class, from_reference},
+		FromField,
+	},
+	math::QsTransform,
+	value::Value,
+	walker::NodeWalker,
+};
+
+/// Single bone of a skeleton.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bone {
+	/// Name of the bone.
+	pub name: String,
+	/// Index of the parent bone, or `None` for a root bone.
+	pub parent: Option<usize>,
+	/// Whether animations should leave the translation of the bone unchanged.
+	pub lock_translation: bool,
+	/// Transform of the bone in its reference pose, relative to its parent.
+	pub reference_pose: QsTransform,
+}
+
+/// Local frame attached to a bone, as stored by `hkaSkeletonLocalFrameOnBone`.
+#[derive(Clone, Debug)]
+pub struct LocalFrame {
+	/// Index of the bone the frame is attached to.
+	pub bone: usize,
+	/// The `hkLocalFrame` node, if set.
+	pub frame: Option<NodeWalker>,
+}
+
+/// Named range of consecutive bones, as stored by `hkaSkeletonPartition`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Partition {
+	/// Name of the partition.
+	pub name: String,
+	/// Index of the first bone in the partition.
+	pub start_bone: usize,
+	/// Number of bones in the partition.
+	pub bone_count: usize,
+}
+
+/// Typed view of an `hkaSkeleton`.
+///
+/// Versions 3 to 5 are supported. Fields absent from older versions, such as
+/// partitions, are left empty.
+#[derive(Clone, Debug)]
+pub struct Skeleton {
+	name: String,
+	bones: Vec<Bone>,
+	children: Vec<Vec<usize>>,
+	reference_floats: Vec<f32>,
+	float_slots: Vec<String>,
+	local_frames: Vec<LocalFrame>,
+	partitions: Vec<Partition>,
+}
+
+impl Skeleton {
+	/// Create a skeleton from its bones, with no float slots, local frames, or
+	/// partitions. Havok stores parent indices as 16 bit integers, which limits
+	/// the number of bones.
+	pub fn new(name: impl Into<String>, bones: Vec<Bone>) -> Result<Self> {
+		if i16::try_from(bones.len()).is_err() {
+			return Err(Error::Invalid(format!(
+				"Skeleton has {} bones, but at most {} are supported.",
+				bones.len(),
+				i16::MAX
+			)));
+		}
+
+		let mut children = vec![Vec::new(); bones.len()];
+		for (index, bone) in bones.iter().enumerate() {
+			if let Some(parent) = bone.parent {
+				children
+					.get_mut(parent)
+					.ok_or_else(|| {
+						Error::Invalid(format!(
+							"Bone {index} has bone index {parent}, but there are {} bones.",
+							bones.len()
+						))
+					})?
+					.push(index);
+			}
+		}
+
+		Ok(Self {
+			name: name.into(),
+			bones,
+			children,
+			reference_floats: Vec::new(),
+			float_slots: Vec::new(),
+			local_frames: Vec::new(),
+			partitions: Vec::new(),
+		})
+	}
+
+	/// Get the name of the skeleton.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the bones of the skeleton, ordered by index.
+	pub fn bones(&self) -> &[Bone] {
+		&self.bones
+	}
+
+	/// Get the bone at the specified index.
+	pub fn bone(&self, index: usize) -> Option<&Bone> {
+		self.bones.get(index)
+	}
+
+	/// Get the index of the first bone with the specified name.
+	pub fn bone_index(&self, name: &str) -> Option<usize> {
+		self.bones.iter().position(|bone| bone.name == name)
+	}
+
+	/// Get the first bone with the specified name.
+	pub fn find_bone(&self, name: &str) -> Option<&Bone> {
+		self.bone_index(name).map(|index| &self.bones[index])
+	}
+
+	/// Get the indices of the bones whose parent is the specified bone.
+	pub fn children(&self, index: usize) -> &[usize] {
+		self.children.get(index).map_or(&[], Vec::as_slice)
+	}
+
+	/// Check if the bone at the specified index has no parent.
+	pub fn is_root(&self, index: usize) -> bool {
+		self.bones
+			.get(index)
+			.is_some_and(|bone| bone.parent.is_none())
+	}
+
+	/// Iterate over the indices of the bones without a parent.
+	pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+		(0..self.bones.len()).filter(|index| self.is_root(*index))
+	}
+
+	/// Iterate over the parent index of each bone, as stored by Havok. Root
+	/// bones have a parent index of `-1`.
+	pub fn parent_indices(&self) -> impl Iterator<Item = i16> + '_ {
+		// Bone counts are checked to fit when the skeleton is created.
+		self.bones.iter().map(|bone| {
+			bone.parent
+				.map_or(-1, |parent| i16::try_from(parent).unwrap_or(i16::MAX))
+		})
+	}
+
+	/// Get the reference pose transform of each bone, relative to its parent.
+	pub fn reference_pose(&self) -> Vec<QsTransform> {
+		self.bones.iter().map(|bone| bone.reference_pose).collect()
+	}
+
+	/// Get the reference values of the float slots.
+	pub fn reference_floats(&self) -> &[f32] {
+		&self.reference_floats
+	}
+
+	/// Get the names of the float slots.
+	pub fn float_slots(&self) -> &[String] {
+		&self.float_slots
+	}
+
+	/// Get the local frames attached to bones.
+	pub fn local_frames(&self) -> &[LocalFrame] {
+		&self.local_frames
+	}
+
+	/// Get the partitions of the skeleton.
+	pub fn partitions(&self) -> &[Partition] {
+		&self.partitions
+	}
+}
+
+/// Get the value of a field that is absent from older class versions.
+fn optional<T: FromField + Default>(node: &NodeWalker, name: &str) -> Result<T> {
+	match node.definition().field(name) {
+		Some(_) => node.get(name),
+		None => Ok(T::default()),
+	}
+}
+
+fn bone_index(bone_count: usize, index: i32, what: &str) -> Result<usize> {
+	usize::try_from(index)
+		.ok()
+		.filter(|index| *index < bone_count)
+		.ok_or_else(|| {
+			Error::Invalid(format!(
+				"{what} has bone index {index}, but there are {bone_count} bones."
+			))
+		})
+}
+
+impl TryFrom<&NodeWalker> for Skeleton {
+	type Error = Error;
+
+	fn try_from(node: &NodeWalker) -> Result<Self> {
+		check_class(node, "hkaSkeleton", None, None)?;
+
+		let bone_nodes = node.get::<Vec<NodeWalker>>("bones")?;
+		let bone_count = bone_nodes.len();
+
+		let parent_indices = node.get::<Vec<i32>>("parentIndices")?;
+		if parent_indices.len() != bone_count {
+			return Err(Error::Invalid(format!(
+				"Expected {bone_count} parent indices, got {}.",
+				parent_indices.len()
+			)));
+		}
+
+		// Some skeletons omit the reference pose entirely, in which case every
+		// bone is left at the identity transform.
+		let mut reference_pose = optional::<Vec<QsTransform>>(node, "referencePose")?;
+		if reference_pose.is_empty() {
+			reference_pose.resize(bone_count, QsTransform::IDENTITY);
+		} else if reference_pose.len() != bone_count {
+			return Err(Error::Invalid(format!(
+				"Expected {bone_count} reference pose transforms, got {}.",
+				reference_pose.len()
+			)));
+		}
+
+		let bones = bone_nodes
+			.iter()
+			.zip(parent_indices)
+			.zip(reference_pose)
+			.enumerate()
+			.map(|(index, ((bone, parent), reference_pose))| {
+				let parent = match parent {
+					-1 => None,
+					parent => Some(bone_index(bone_count, parent, &format!("Bone {index}"))?),
+				};
+				Ok(Bone {
+					name: optional(bone, "name")?,
+					parent,
+					lock_translation: optional(bone, "lockTranslation")?,
+					reference_pose,
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		let local_frames = optional::<Vec<NodeWalker>>(node, "localFrames")?
+			.iter()
+			.map(|frame| {
+				Ok(LocalFrame {
+					bone: bone_index(bone_count, frame.get("boneIndex")?, "Local frame")?,
+					frame: frame.get("localFrame")?,
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		let partitions = optional::<Vec<NodeWalker>>(node, "partitions")?
+			.iter()
+			.map(|partition| {
+				let partition = Partition {
+					name: partition.get("name")?,
+					start_bone: partition.get("startBoneIndex")?,
+					bone_count: partition.get("numBones")?,
+				};
+				if partition.start_bone + partition.bone_count > bone_count {
+					return Err(Error::Invalid(format!(
+						"Partition {} exceeds {bone_count} bones.",
+						partition.name
+					)));
+				}
+				Ok(partition)
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(Self {
+			reference_floats: optional(node, "referenceFloats")?,
+			float_slots: optional(node, "floatSlots")?,
+			local_frames,
+			partitions,
+			..Self::new(optional::<String>(node, "name")?, bones)?
+		})
+	}
+}
+
+impl TryFrom<NodeWalker> for Skeleton {
+	type Error = Error;
+
+	fn try_from(node: NodeWalker) -> Result<Self> {
+		Self::try_from(&node)
+	}
+}
+
+impl FromField for Skeleton {
+	fn from_field(node: &NodeWalker, value: &Value) -> Result<Self> {
+		from_reference(node, value)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		math::QsTransform,
+		node::FieldKind,
+		testing::{self, definition, node, transform, versioned_definition, walker},
+		value::{Value, NULL_NODE},
+	};
+
+	use super::{Partition, Skeleton};
+
+	fn vector(values: impl IntoIterator<Item = Value>) -> Option<Value> {
+		Some(Value::Vector(values.into_iter().collect()))
+	}
+
+	#[test]
+	fn skeleton() {
+		let local_frame = definition(
+			"hkaSkeletonLocalFrameOnBone",
+			None,
+			vec![
+				("localFrame", FieldKind::Reference("hkLocalFrame".into())),
+				("boneIndex", FieldKind::Integer),
+			],
+		);
+		let partition = versioned_definition(
+			"hkaSkeletonPartition",
+			1,
+			None,
+			vec![
+				("name", FieldKind::String),
+				("startBoneIndex", FieldKind::Integer),
+				("numBones", FieldKind::Integer),
+			],
+		);
+		let (skeleton, bone) = testing::skeleton(
+			5,
+			vec![
+				(
+					"referencePose",
+					FieldKind::Vector(FieldKind::Array(FieldKind::Float.into(), 12).into()),
+				),
+				(
+					"referenceFloats",
+					FieldKind::Vector(FieldKind::Float.into()),
+				),
+				("floatSlots", FieldKind::Vector(FieldKind::String.into())),
+				(
+					"localFrames",
+					FieldKind::Vector(
+						FieldKind::Struct("hkaSkeletonLocalFrameOnBone".into()).into(),
+					),
+				),
+				(
+					"partitions",
+					FieldKind::Vector(FieldKind::Struct("hkaSkeletonPartition".into()).into()),
+				),
+			],
+		);
+
+		let walker = walker(vec![
+			node(
+				&skeleton,
+				vec![
+					Some(Value::String("skeleton".into())),
+					vector([Value::I32(-1), Value::I32(0), Value::I32(0), Value::I32(-1)]),
+					vector((1..=4).map(Value::Node)),
+					vector((0..4).map(|index| transform(index as f32))),
+					vector([Value::F32(0.5)]),
+					vector([Value::String("slot".into())]),
+					vector([Value::Node(5)]),
+					vector([Value::Node(6)]),
+				],
+			),
+			node(&bone, vec![Some(Value::String("root".into())), None]),
+			node(&bone, vec![Some(Value::String("left".into())), None]),
+			node(
+				&bone,
+				vec![Some(Value::String("right".into())), Some(Value::U8(1))],
+			),
+			node(&bone, vec![Some(Value::String("other".into())), None]),
+			node(
+				&local_frame,
+				vec![Some(Value::Node(NULL_NODE)), Some(Value::I32(2))],
+			),
+			node(
+				&partition,
+				vec![
+					Some(Value::String("body".into())),
+					Some(Value::I32(0)),
+					Some(Value::I32(3)),
+				],
+			),
+		]);
+
+		let skeleton = Skeleton::try_from(&walker).unwrap();
+		assert_eq!(skeleton.name(), "skeleton");
+		assert_eq!(skeleton.bones().len(), 4);
+		assert_eq!(skeleton.bone_index("right"), Some(2));
+		assert_eq!(skeleton.bone_index("missing"), None);
+
+		let right = skeleton.find_bone("right").unwrap();
+		assert_eq!(right.parent, Some(0));
+		assert!(right.lock_translation);
+		assert_eq!(right.reference_pose.translation.x, 2.);
+
+		assert_eq!(skeleton.children(0), [1, 2]);
+		assert!(skeleton.children(1).is_empty());
+		assert_eq!(skeleton.roots().collect::<Vec<_>>(), [0, 3]);
+		assert_eq!(
+			skeleton.parent_indices().collect::<Vec<_>>(),
+			[-1, 0, 0, -1]
+		);
+
+		assert_eq!(skeleton.reference_floats(), [0.5]);
+		assert_eq!(skeleton.float_slots(), ["slot"]);
+		assert_eq!(skeleton.local_frames()[0].bone, 2);
+		assert!(skeleton.local_frames()[0].frame.is_none());
+		assert_eq!(
+			skeleton.partitions(),
+			[Partition {
+				name: "body".into(),
+				start_bone: 0,
+				bone_count: 3,
+			}]
+		);
+	}
+
+	#[test]
+	fn older_version() {
+		let (skeleton, bone) = testing::skeleton(3, vec![]);
+
+		let valid = walker(vec![
+			node(
+				&skeleton,
+				vec![
+					None,
+					vector([Value::I32(-1), Value::I32(0)]),
+					vector([Value::Node(1), Value::Node(2)]),
+				],
+			),
+			node(&bone, vec![Some(Value::String("root".into())), None]),
+			node(&bone, vec![None, None]),
+		]);
+		let parsed = Skeleton::try_from(&valid).unwrap();
+		assert_eq!(parsed.name(), "");
+		assert_eq!(parsed.bones()[1].name, "");
+		assert_eq!(parsed.bones()[1].reference_pose, QsTransform::IDENTITY);
+		assert!(parsed.partitions().is_empty());
+
+		let invalid = walker(vec![
+			node(
+				&skeleton,
+				vec![
+					None,
+					vector([Value::I32(-1), Value::I32(2)]),
+					vector([Value::Node(1), Value::Node(2)]),
+				],
+			),
+			node(&bone, vec![None, None]),
+			node(&bone, vec![None, None]),
+		]);
+		assert_eq!(
+			Skeleton::try_from(&invalid).unwrap_err().to_string(),
+			"Invalid: Bone 1 has bone index 2, but there are 2 bones."
+		);
+
+		let bone = parsed.bones()[1].clone();
+		let bones = vec![bone; i16::MAX as usize + 1];
+		assert_eq!(
+			Skeleton::new("large", bones).unwrap_err().to_string(),
+			"Invalid: Skeleton has 32768 bones, but at most 32767 are supported."
+		);
+	}
+}
@@ -0,0 +1,979 @@
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4};
+
+use crate::{
+	error::{Error, Result},
+	from_node::{
+		__private::{check_class, from_reference},
+		FromField,
+	},
+	math::{QsTransform, Quaternion, Vector4},
+	value::Value,
+	walker::NodeWalker,
+};
+
+use super::Animation;
+
+/// Typed view of an `hkaSplineCompressedAnimation`.
+///
+/// Every block of the animation is decoded up front, such that sampling
+/// cannot fail. Each transform track stores its translation, rotation, and
+/// scale separately, as either a constant value or a quantized B-spline over
+/// the frames of the block.
+///
+/// Float tracks are stored in the same manner as a single component of a
+/// translation track, quantized to 16 bits.
+///
+/// Rotations quantized as `THREECOMP24` or `STRAIGHT16` are not supported, and
+/// animations using them fail to load. This is a known limitation.
+#[derive(Clone, Debug)]
+pub struct SplineCompressedAnimation {
+	duration: f32,
+	frame_count: usize,
+	frame_duration: f32,
+	max_frames_per_block: usize,
+	transform_track_count: usize,
+	float_track_count: usize,
+	blocks: Vec<Block>,
+}
+
+impl SplineCompressedAnimation {
+	/// Find the block containing a time, and the frame within that block.
+	/// Consecutive blocks share a frame, such that the last frame of a block is
+	/// the first frame of the next.
+	fn locate(&self, time: f32) -> (usize, f32) {
+		let frame = match self.frame_duration > 0. {
+			true => time.clamp(0., self.duration) / self.frame_duration,
+			false => 0.,
+		};
+		let frames_per_block = self.max_frames_per_block.saturating_sub(1).max(1) as f32;
+		let block = ((frame / frames_per_block) as usize).min(self.blocks.len().saturating_sub(1));
+		(block, frame - block as f32 * frames_per_block)
+	}
+}
+
+impl Animation for SplineCompressedAnimation {
+	fn duration(&self) -> f32 {
+		self.duration
+	}
+
+	fn frame_count(&self) -> usize {
+		self.frame_count
+	}
+
+	fn transform_track_count(&self) -> usize {
+		self.transform_track_count
+	}
+
+	fn float_track_count(&self) -> usize {
+		self.float_track_count
+	}
+
+	fn sample_transform(&self, track: usize, time: f32) -> Option<QsTransform> {
+		let (block, frame) = self.locate(time);
+		self.blocks
+			.get(block)?
+			.transforms
+			.get(track)
+			.map(|track| track.sample(frame))
+	}
+
+	fn sample_float(&self, track: usize, time: f32) -> Option<f32> {
+		let (block, frame) = self.locate(time);
+		self.blocks
+			.get(block)?
+			.floats
+			.get(track)
+			.map(|track| track.sample(frame).x)
+	}
+}
+
+impl TryFrom<&NodeWalker> for SplineCompressedAnimation {
+	type Error = Error;
+
+	fn try_from(node: &NodeWalker) -> Result<Self> {
+		check_class(node, "hkaSplineCompressedAnimation", None, None)?;
+
+		// Sampling clamps times to the duration, which requires it to be valid.
+		let duration = node.get::<f32>("duration")?;
+		let frame_duration = node.get::<f32>("frameDuration")?;
+		if !(duration.is_finite()
+			&& duration >= 0.
+			&& frame_duration.is_finite()
+			&& frame_duration >= 0.)
+		{
+			return Err(Error::Invalid(format!(
+				"Invalid duration {duration}, with frame duration {frame_duration}."
+			)));
+		}
+
+		let transform_track_count = node.get::<usize>("numberOfTransformTracks")?;
+		let float_track_count = node.get::<usize>("numberOfFloatTracks")?;
+		let mask_size = node.get::<usize>("maskAndQuantizationSize")?;
+		let block_count = node.get::<usize>("numBlocks")?;
+		let block_offsets = node.get::<Vec<usize>>("blockOffsets")?;
+		let float_offsets = match float_track_count {
+			0 => vec![0; block_count],
+			_ => node.get::<Vec<usize>>("floatBlockOffsets")?,
+		};
+		if block_offsets.len() != block_count || float_offsets.len() != block_count {
+			return Err(Error::Invalid(format!(
+				"Expected {block_count} block offsets, got {} and {} float block offsets.",
+				block_offsets.len(),
+				float_offsets.len()
+			)));
+		}
+		if block_offsets.is_empty() && transform_track_count > 0 {
+			return Err(Error::Invalid("Expected at least one block.".into()));
+		}
+
+		let data = node.get::<Vec<u8>>("data")?;
+		let mut reader = Reader {
+			data: &data,
+			offset: 0,
+			big_endian: node.get::<i32>("endian")? != 0,
+		};
+
+		let blocks = block_offsets
+			.iter()
+			.zip(float_offsets)
+			.enumerate()
+			.map(|(block, (offset, float_offset))| {
+				reader.offset = *offset;
+				read_block(
+					&mut reader,
+					transform_track_count,
+					float_track_count,
+					mask_size,
+					float_offset,
+				)
+				.map_err(|error| Error::Invalid(format!("Block {block}: {error}")))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(Self {
+			duration,
+			frame_count: node.get("numFrames")?,
+			frame_duration,
+			max_frames_per_block: node.get("maxFramesPerBlock")?,
+			transform_track_count,
+			float_track_count,
+			blocks,
+		})
+	}
+}
+
+impl TryFrom<NodeWalker> for SplineCompressedAnimation {
+	type Error = Error;
+
+	fn try_from(node: NodeWalker) -> Result<Self> {
+		Self::try_from(&node)
+	}
+}
+
+impl FromField for SplineCompressedAnimation {
+	fn from_field(node: &NodeWalker, value: &Value) -> Result<Self> {
+		from_reference(node, value)
+	}
+}
+
+/// Read the masks of a block, followed by the data of each transform track,
+/// and the data of each float track at an offset from the start of the block.
+fn read_block(
+	reader: &mut Reader,
+	transform_track_count: usize,
+	float_track_count: usize,
+	mask_size: usize,
+	float_offset: usize,
+) -> Result<Block> {
+	let start = reader.offset;
+	let masks = (0..transform_track_count)
+		.map(|_| reader.bytes::<4>())
+		.collect::<Result<Vec<_>>>()?;
+	let float_masks = (0..float_track_count)
+		.map(|_| reader.u8())
+		.collect::<Result<Vec<_>>>()?;
+
+	reader.offset = start + mask_size;
+	let transforms = masks
+		.into_iter()
+		.enumerate()
+		.map(|(track, [quantization, translation, rotation, scale])| {
+			let read = |reader: &mut Reader| {
+				Ok(TransformTrack {
+					translation: read_vector(
+						reader,
+						translation,
+						ScalarQuantization::from_bits(quantization & 0x3)?,
+						Vector4::ZERO,
+					)?,
+					rotation: read_rotation(
+						reader,
+						rotation,
+						RotationQuantization::from_bits((quantization >> 2) & 0xf)?,
+					)?,
+					scale: read_vector(
+						reader,
+						scale,
+						ScalarQuantization::from_bits(quantization >> 6)?,
+						Vector4::ONE,
+					)?,
+				})
+			};
+			read(reader).map_err(|error: Error| Error::Invalid(format!("Track {track}: {error}")))
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	reader.offset = start + float_offset;
+	let floats = float_masks
+		.into_iter()
+		.enumerate()
+		.map(|(track, mask)| {
+			// Files do not record the quantization of float tracks. The Havok SDK
+			// defaults `floatQuantizationType` of `hkaSplineCompressedAnimation`'s
+			// `TrackCompressionParams` to 16 bits, which is assumed here. A float is
+			// a single component, so only the static and spline flags of `x` apply.
+			read_vector(
+				reader,
+				mask & 0x11,
+				ScalarQuantization::Bits16,
+				Vector4::ZERO,
+			)
+			.map_err(|error| Error::Invalid(format!("Float track {track}: {error}")))
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(Block { transforms, floats })
+}
+
+/// Read a translation or scale track. The low four bits of the flags mark
+/// components with a static value, and the high four bits mark components
+/// stored in the spline.
+fn read_vector(
+	reader: &mut Reader,
+	flags: u8,
+	quantization: ScalarQuantization,
+	default: Vector4,
+) -> Result<Track> {
+	let mut value = default.to_array();
+	if flags & 0xf0 == 0 {
+		for (component, value) in value.iter_mut().enumerate().take(3) {
+			if flags & (1 << component) != 0 {
+				*value = reader.f32()?;
+			}
+		}
+		return Ok(Track::Constant(value.into()));
+	}
+
+	let (count, degree, knots) = read_spline_header(reader)?;
+	reader.align(4);
+
+	let mut ranges = [None; 3];
+	for (component, range) in ranges.iter_mut().enumerate() {
+		if flags & (0x10 << component) != 0 {
+			*range = Some((reader.f32()?, reader.f32()?));
+		} else if flags & (1 << component) != 0 {
+			value[component] = reader.f32()?;
+		}
+	}
+
+	let points = (0..count)
+		.map(|_| {
+			let mut point = value;
+			for (component, range) in ranges.iter().enumerate() {
+				if let Some((min, max)) = range {
+					point[component] = min + (max - min) * quantization.read(reader)?;
+				}
+			}
+			Ok(point.into())
+		})
+		.collect::<Result<Vec<_>>>()?;
+	reader.align(4);
+
+	Ok(Track::Spline {
+		degree,
+		knots,
+		points,
+	})
+}
+
+/// Read a rotation track. Rotations are either entirely static or entirely
+/// stored in the spline.
+fn read_rotation(
+	reader: &mut Reader,
+	flags: u8,
+	quantization: RotationQuantization,
+) -> Result<Track> {
+	let track = if flags & 0xf0 != 0 {
+		let (count, degree, knots) = read_spline_header(reader)?;
+		reader.align(quantization.alignment());
+		let points = (0..count)
+			.map(|_| quantization.read(reader))
+			.collect::<Result<Vec<_>>>()?;
+		Track::Spline {
+			degree,
+			knots,
+			points,
+		}
+	} else if flags & 0x0f != 0 {
+		reader.align(quantization.alignment());
+		Track::Constant(quantization.read(reader)?)
+	} else {
+		Track::Constant(Quaternion::IDENTITY.to_array().into())
+	};
+	reader.align(4);
+
+	Ok(track)
+}
+
+/// Read the number of control points, degree, and knots of a spline.
+fn read_spline_header(reader: &mut Reader) -> Result<(usize, usize, Vec<f32>)> {
+	let count = usize::from(reader.u16()?) + 1;
+	let degree = usize::from(reader.u8()?);
+	if count <= degree {
+		return Err(Error::Invalid(format!(
+			"Spline of degree {degree} has {count} control points."
+		)));
+	}
+
+	let knots = (0..count + degree + 1)
+		.map(|_| reader.u8().map(f32::from))
+		.collect::<Result<Vec<_>>>()?;
+	if knots.windows(2).any(|pair| pair[0] > pair[1]) {
+		return Err(Error::Invalid(format!(
+			"Spline knots are not in order: {knots:?}."
+		)));
+	}
+	Ok((count, degree, knots))
+}
+
+#[derive(Clone, Debug)]
+struct Block {
+	transforms: Vec<TransformTrack>,
+	/// Float tracks, stored in the `x` component.
+	floats: Vec<Track>,
+}
+
+#[derive(Clone, Debug)]
+struct TransformTrack {
+	translation: Track,
+	rotation: Track,
+	scale: Track,
+}
+
+impl TransformTrack {
+	fn sample(&self, frame: f32) -> QsTransform {
+		QsTransform::new(
+			self.translation.sample(frame),
+			Quaternion::from(self.rotation.sample(frame).to_array()).normalize(),
+			self.scale.sample(frame),
+		)
+	}
+}
+
+/// Values of a track within a single block. Rotations are stored in the
+/// components of a vector, and normalized after sampling.
+#[derive(Clone, Debug)]
+enum Track {
+	Constant(Vector4),
+	Spline {
+		degree: usize,
+		knots: Vec<f32>,
+		points: Vec<Vector4>,
+	},
+}
+
+impl Track {
+	/// Evaluate the track at a frame within its block.
+	fn sample(&self, frame: f32) -> Vector4 {
+		let (degree, knots, points) = match self {
+			Self::Constant(value) => return *value,
+			Self::Spline {
+				degree,
+				knots,
+				points,
+			} => (*degree, knots, points),
+		};
+
+		// Find the knot span containing the frame, clamped to the range of the
+		// spline, and evaluate the non-zero basis functions over it.
+		let count = points.len();
+		let frame = frame.clamp(knots[degree], knots[count]);
+		let span = degree
+			+ knots[degree..count]
+				.partition_point(|knot| *knot <= frame)
+				.max(1) - 1;
+
+		let mut basis = vec![0.; degree + 1];
+		basis[0] = 1.;
+		for i in 1..=degree {
+			for j in (0..i).rev() {
+				let low = knots[span - j];
+				let range = knots[span + i - j] - low;
+				let scale = match range > 0. {
+					true => (frame - low) / range,
+					false => 0.,
+				};
+				let weight = basis[j] * scale;
+				basis[j + 1] += basis[j] - weight;
+				basis[j] = weight;
+			}
+		}
+
+		basis
+			.iter()
+			.enumerate()
+			.fold(Vector4::ZERO, |sum, (index, weight)| {
+				sum + points[span - index] * *weight
+			})
+	}
+}
+
+/// Quantization of translation and scale spline components, stored relative
+/// to the range of the component.
+#[derive(Clone, Copy, Debug)]
+enum ScalarQuantization {
+	Bits8,
+	Bits16,
+}
+
+impl ScalarQuantization {
+	fn from_bits(bits: u8) -> Result<Self> {
+		match bits {
+			0 => Ok(Self::Bits8),
+			1 => Ok(Self::Bits16),
+			other => Err(Error::Invalid(format!(
+				"Unknown scalar quantization {other}."
+			))),
+		}
+	}
+
+	/// Read a value within the range of zero to one.
+	fn read(self, reader: &mut Reader) -> Result<f32> {
+		match self {
+			Self::Bits8 => Ok(f32::from(reader.u8()?) / f32::from(u8::MAX)),
+			Self::Bits16 => Ok(f32::from(reader.u16()?) / f32::from(u16::MAX)),
+		}
+	}
+}
+
+/// Quantization of rotations.
+#[derive(Clone, Copy, Debug)]
+enum RotationQuantization {
+	Polar32,
+	ThreeComp40,
+	ThreeComp48,
+	ThreeComp24,
+	Straight16,
+	Uncompressed,
+}
+
+impl RotationQuantization {
+	fn from_bits(bits: u8) -> Result<Self> {
+		match bits {
+			0 => Ok(Self::Polar32),
+			1 => Ok(Self::ThreeComp40),
+			2 => Ok(Self::ThreeComp48),
+			3 => Ok(Self::ThreeComp24),
+			4 => Ok(Self::Straight16),
+			5 => Ok(Self::Uncompressed),
+			other => Err(Error::Invalid(format!(
+				"Unknown rotation quantization {other}."
+			))),
+		}
+	}
+
+	fn alignment(self) -> usize {
+		match self {
+			Self::Polar32 | Self::Uncompressed => 4,
+			Self::ThreeComp48 | Self::Straight16 => 2,
+			Self::ThreeComp40 | Self::ThreeComp24 => 1,
+		}
+	}
+
+	fn read(self, reader: &mut Reader) -> Result<Vector4> {
+		match self {
+			Self::Polar32 => Ok(polar32(reader.u32()?)),
+			Self::ThreeComp40 => Ok(three_comp40(reader.uint::<5>()?)),
+			Self::ThreeComp48 => Ok(three_comp48([reader.u16()?, reader.u16()?, reader.u16()?])),
+			Self::Uncompressed => Ok(Vector4::new(
+				reader.f32()?,
+				reader.f32()?,
+				reader.f32()?,
+				reader.f32()?,
+			)),
+			Self::ThreeComp24 | Self::Straight16 => Err(Error::Invalid(format!(
+				"Unsupported rotation quantization {self:?}."
+			))),
+		}
+	}
+}
+
+/// Decode a rotation stored as a 10 bit `w` component, and the direction of
+/// the vector part in polar coordinates, with the signs of each component in
+/// the high four bits.
+fn polar32(value: u32) -> Vector4 {
+	const R_MASK: u32 = (1 << 10) - 1;
+	const PHI_SCALE: f32 = FRAC_PI_2 / 511.;
+
+	let r = (value >> 18 & R_MASK) as f32 / R_MASK as f32;
+	let w = 1. - r * r;
+
+	let phi_theta = (value & 0x3ffff) as f32;
+	let mut phi = phi_theta.sqrt().floor();
+	let mut theta = 0.;
+	if phi > 0. {
+		theta = FRAC_PI_4 * (phi_theta - phi * phi) / phi;
+		phi *= PHI_SCALE;
+	}
+
+	let magnitude = (1. - w * w).max(0.).sqrt();
+	let sign = |bit: u32| match value & (1 << bit) != 0 {
+		true => -1.,
+		false => 1.,
+	};
+	Vector4::new(
+		phi.sin() * theta.cos() * magnitude * sign(28),
+		phi.sin() * theta.sin() * magnitude * sign(29),
+		phi.cos() * magnitude * sign(30),
+		w * sign(31),
+	)
+}
+
+/// Decode a rotation stored as three 12 bit components, the index of the
+/// omitted largest component, and its sign.
+fn three_comp40(value: u64) -> Vector4 {
+	const MASK: u64 = (1 << 12) - 1;
+	const HALF: f32 = (MASK >> 1) as f32;
+
+	let component = |shift: u32| ((value >> shift & MASK) as f32 - HALF) * (FRAC_1_SQRT_2 / HALF);
+	smallest_three(
+		[component(0), component(12), component(24)],
+		(value >> 36 & 0x3) as usize,
+		value >> 38 & 1 != 0,
+	)
+}
+
+/// Decode a rotation stored as three 15 bit components. The index of the
+/// omitted largest component is stored in the high bits of the first two
+/// components, and its sign in the high bit of the third.
+fn three_comp48([x, y, z]: [u16; 3]) -> Vector4 {
+	const MASK: u16 = (1 << 15) - 1;
+	const HALF: f32 = (MASK >> 1) as f32;
+
+	let component = |value: u16| ((value & MASK) as f32 - HALF) * (FRAC_1_SQRT_2 / HALF);
+	smallest_three(
+		[component(x), component(y), component(z)],
+		usize::from((y >> 14 & 0x2) | (x >> 15)),
+		z >> 15 != 0,
+	)
+}
+
+/// Rebuild a unit quaternion from its three smallest components.
+fn smallest_three(values: [f32; 3], largest: usize, negative: bool) -> Vector4 {
+	let mut missing = (1. - values.iter().map(|value| value * value).sum::<f32>())
+		.max(0.)
+		.sqrt();
+	if negative {
+		missing = -missing;
+	}
+
+	let mut values = values.into_iter();
+	let components = std::array::from_fn(|index| match index == largest {
+		true => missing,
+		false => values.next().unwrap_or_default(),
+	});
+	Vector4::from(components)
+}
+
+/// Cursor over the compressed data of an animation.
+struct Reader<'a> {
+	data: &'a [u8],
+	offset: usize,
+	big_endian: bool,
+}
+
+impl Reader<'_> {
+	fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+		let bytes = self.data.get(self.offset..self.offset + N).ok_or_else(|| {
+			Error::Invalid(format!("Unexpected end of data at offset {}.", self.offset))
+		})?;
+		self.offset += N;
+		Ok(bytes.try_into().unwrap())
+	}
+
+	/// Read an unsigned integer of `N` bytes, in the endianness of the data.
+	fn uint<const N: usize>(&mut self) -> Result<u64> {
+		let bytes = self.bytes::<N>()?;
+		let combine = |value: u64, byte: &u8| value << 8 | u64::from(*byte);
+		Ok(match self.big_endian {
+			true => bytes.iter().fold(0, combine),
+			false => bytes.iter().rev().fold(0, combine),
+		})
+	}
+
+	fn u8(&mut self) -> Result<u8> {
+		self.bytes::<1>().map(|[byte]| byte)
+	}
+
+	fn u16(&mut self) -> Result<u16> {
+		self.uint::<2>().map(|value| value as u16)
+	}
+
+	fn u32(&mut self) -> Result<u32> {
+		self.uint::<4>().map(|value| value as u32)
+	}
+
+	fn f32(&mut self) -> Result<f32> {
+		self.u32().map(f32::from_bits)
+	}
+
+	fn align(&mut self, alignment: usize) {
+		self.offset = self.offset.next_multiple_of(alignment);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
+
+	use crate::{
+		animation::Animation,
+		error::Result,
+		math::Vector4,
+		node::FieldKind,
+		testing::{assert_near, definition, node, walker},
+		value::Value,
+	};
+
+	use super::{polar32, three_comp40, three_comp48, SplineCompressedAnimation};
+
+	/// Tolerance for values decoded from quantized data.
+	const EPSILON: f32 = 1e-3;
+
+	#[test]
+	fn quantization() {
+		let identity = Vector4::new(0., 0., 0., 1.);
+		assert_near(polar32(0), identity, EPSILON);
+
+		// Rotations of 90 degrees around each axis, with r of sqrt(1 - w)
+		// quantized to 554. Phi is stored as its square, in 511ths of 90
+		// degrees from z, plus theta in steps of 45 / phi degrees from x.
+		let r = 554 << 18;
+		let x = 511 * 511;
+		let y = 511 * 511 + 2 * 511;
+		assert_near(
+			polar32(r),
+			Vector4::new(0., 0., FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+			EPSILON,
+		);
+		assert_near(
+			polar32(r | x),
+			Vector4::new(FRAC_1_SQRT_2, 0., 0., FRAC_1_SQRT_2),
+			EPSILON,
+		);
+		assert_near(
+			polar32(r | y),
+			Vector4::new(0., FRAC_1_SQRT_2, 0., FRAC_1_SQRT_2),
+			EPSILON,
+		);
+
+		// Halfway between x and y, with theta of 45 degrees.
+		let xy = 511 * 511 + 511;
+		assert_near(
+			polar32(r | xy),
+			Vector4::new(0.5, 0.5, 0., FRAC_1_SQRT_2),
+			EPSILON,
+		);
+		assert_near(
+			polar32(r | xy | 1 << 28 | 1 << 29),
+			Vector4::new(-0.5, -0.5, 0., FRAC_1_SQRT_2),
+			EPSILON,
+		);
+
+		// Each sign bit negates a single component.
+		assert_near(
+			polar32(r | x | 1 << 28),
+			Vector4::new(-FRAC_1_SQRT_2, 0., 0., FRAC_1_SQRT_2),
+			EPSILON,
+		);
+		assert_near(
+			polar32(r | y | 1 << 29),
+			Vector4::new(0., -FRAC_1_SQRT_2, 0., FRAC_1_SQRT_2),
+			EPSILON,
+		);
+		assert_near(
+			polar32(r | 1 << 30),
+			Vector4::new(0., 0., -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+			EPSILON,
+		);
+		assert_near(
+			polar32(r | 1 << 31),
+			Vector4::new(0., 0., FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+			EPSILON,
+		);
+
+		assert_near(
+			three_comp40(2047 | 2047 << 12 | 2047 << 24 | 3 << 36),
+			identity,
+			EPSILON,
+		);
+		assert_near(
+			three_comp48([16383 | 1 << 15, 16383 | 1 << 15, 16383]),
+			identity,
+			EPSILON,
+		);
+
+		// Rotation of 90 degrees around z, with the largest component omitted.
+		let expected = Vector4::new(0., 0., FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
+		assert_near(
+			three_comp48([16383 | 1 << 15, 16383 | 1 << 15, 32766 | 1 << 15]),
+			expected,
+			EPSILON,
+		);
+		assert_near(
+			three_comp40(2047 | 2047 << 12 | 4094 << 24 | 3 << 36 | 1 << 38),
+			expected,
+			EPSILON,
+		);
+	}
+
+	struct Data(Vec<u8>);
+
+	impl Data {
+		fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+			self.0.extend_from_slice(bytes);
+			self
+		}
+
+		fn u16(&mut self, value: u16) -> &mut Self {
+			self.bytes(&value.to_le_bytes())
+		}
+
+		fn f32(&mut self, value: f32) -> &mut Self {
+			self.bytes(&value.to_le_bytes())
+		}
+
+		fn align(&mut self, alignment: usize) -> &mut Self {
+			self.0.resize(self.0.len().next_multiple_of(alignment), 0);
+			self
+		}
+	}
+
+	/// Header fields of a compressed animation, with every block sharing the
+	/// same mask and quantization size.
+	struct Header {
+		duration: f32,
+		transform_tracks: usize,
+		float_tracks: usize,
+		frames: usize,
+		max_frames_per_block: usize,
+		mask_size: usize,
+		block_offsets: Vec<usize>,
+		float_offsets: Vec<usize>,
+	}
+
+	fn animation(data: Vec<u8>, header: &Header) -> Result<SplineCompressedAnimation> {
+		let animation = definition(
+			"hkaAnimation",
+			None,
+			vec![
+				("duration", FieldKind::Float),
+				("numberOfTransformTracks", FieldKind::Integer),
+				("numberOfFloatTracks", FieldKind::Integer),
+			],
+		);
+		let spline = definition(
+			"hkaSplineCompressedAnimation",
+			Some(&animation),
+			vec![
+				("numFrames", FieldKind::Integer),
+				("numBlocks", FieldKind::Integer),
+				("maxFramesPerBlock", FieldKind::Integer),
+				("maskAndQuantizationSize", FieldKind::Integer),
+				("frameDuration", FieldKind::Float),
+				("blockOffsets", FieldKind::Vector(FieldKind::Integer.into())),
+				(
+					"floatBlockOffsets",
+					FieldKind::Vector(FieldKind::Integer.into()),
+				),
+				("data", FieldKind::Vector(FieldKind::Byte.into())),
+				("endian", FieldKind::Integer),
+			],
+		);
+
+		let integer = |value: usize| Value::I32(value as i32);
+		let offsets =
+			|offsets: &[usize]| Value::Vector(offsets.iter().copied().map(integer).collect());
+		let walker = walker(vec![node(
+			&spline,
+			vec![
+				Some(Value::F32(header.duration)),
+				Some(integer(header.transform_tracks)),
+				Some(integer(header.float_tracks)),
+				Some(integer(header.frames)),
+				Some(integer(header.block_offsets.len())),
+				Some(integer(header.max_frames_per_block)),
+				Some(integer(header.mask_size)),
+				Some(Value::F32(
+					header.duration / header.frames.saturating_sub(1).max(1) as f32,
+				)),
+				Some(offsets(&header.block_offsets)),
+				Some(offsets(&header.float_offsets)),
+				Some(Value::Vector(data.into_iter().map(Value::U8).collect())),
+				None,
+			],
+		)]);
+		SplineCompressedAnimation::try_from(&walker)
+	}
+
+	#[test]
+	fn decompress() {
+		let mut data = Data(Vec::new());
+		data
+			// Track 0: 8 bit translation spline in x and static in y, with an
+			// uncompressed static rotation.
+			.bytes(&[5 << 2, 0x12, 0x01, 0x00])
+			// Track 1: 48 bit rotation spline, with a static scale.
+			.bytes(&[2 << 2, 0x00, 0xf0, 0x07])
+			// Float track, stored as a spline.
+			.bytes(&[0x10])
+			.align(4);
+
+		data.u16(1)
+			.bytes(&[1, 0, 0, 1, 1])
+			.align(4)
+			.f32(0.)
+			.f32(10.)
+			.f32(5.)
+			.bytes(&[0, 255])
+			.align(4)
+			.f32(0.)
+			.f32(0.)
+			.f32(0.6)
+			.f32(0.8);
+
+		data.u16(1)
+			.bytes(&[1, 0, 0, 1, 1])
+			.align(2)
+			.u16(16383 | 1 << 15)
+			.u16(16383 | 1 << 15)
+			.u16(16383)
+			.u16(16383 | 1 << 15)
+			.u16(16383 | 1 << 15)
+			.u16(32766)
+			.align(4)
+			.f32(2.)
+			.f32(2.)
+			.f32(2.);
+
+		let mut header = Header {
+			duration: 1.,
+			transform_tracks: 2,
+			float_tracks: 1,
+			frames: 2,
+			max_frames_per_block: 256,
+			mask_size: 12,
+			block_offsets: vec![0],
+			float_offsets: vec![data.0.len()],
+		};
+		data.u16(1)
+			.bytes(&[1, 0, 0, 1, 1])
+			.align(4)
+			.f32(0.)
+			.f32(4.)
+			.u16(0)
+			.u16(u16::MAX);
+
+		for duration in [f32::NAN, -1.] {
+			header.duration = duration;
+			assert!(animation(data.0.clone(), &header).is_err());
+		}
+		header.duration = 1.;
+
+		// Knots that decrease are rejected, rather than breaking sampling.
+		let mut unordered = data.0.clone();
+		unordered[15..19].copy_from_slice(&[1, 0, 0, 1]);
+		assert_eq!(
+			animation(unordered, &header)
+				.unwrap_err()
+				.to_string(),
+			"Invalid: Block 0: Invalid: Track 0: Invalid: Spline knots are not in order: [1.0, 0.0, 0.0, 1.0]."
+		);
+
+		let animation = animation(data.0, &header).unwrap();
+		assert_eq!(animation.duration(), 1.);
+		assert_eq!(animation.frame_count(), 2);
+		assert_eq!(animation.float_track_count(), 1);
+
+		let transforms = animation.sample_transforms(0.5);
+		assert_eq!(transforms.len(), 2);
+		assert_near(
+			transforms[0].translation,
+			Vector4::new(5., 5., 0., 0.),
+			EPSILON,
+		);
+		assert_near(
+			transforms[0].rotation.to_array().into(),
+			Vector4::new(0., 0., 0.6, 0.8),
+			EPSILON,
+		);
+		assert_near(transforms[0].scale, Vector4::ONE, EPSILON);
+
+		let half = (FRAC_PI_4 / 2.).sin_cos();
+		assert_near(
+			transforms[1].rotation.to_array().into(),
+			Vector4::new(0., 0., half.0, half.1),
+			EPSILON,
+		);
+		assert_near(transforms[1].scale, Vector4::new(2., 2., 2., 1.), EPSILON);
+
+		let end = animation.sample_transform(0, 5.).unwrap();
+		assert_near(end.translation, Vector4::new(10., 5., 0., 0.), EPSILON);
+		assert!(animation.sample_transform(2, 0.).is_none());
+		assert_eq!(animation.sample_floats(0.5), [2.]);
+		assert_eq!(animation.sample_float(0, 1.), Some(4.));
+	}
+
+	#[test]
+	fn blocks() {
+		// A translation spline in x over three frames, quantized to the byte
+		// values themselves. Each block is offset, such that sampling shows
+		// which block a time was located in.
+		let block = |data: &mut Data, min: f32| {
+			data.bytes(&[0, 0x10, 0x00, 0x00])
+				.u16(2)
+				.bytes(&[1, 0, 0, 1, 2, 2])
+				.align(4)
+				.f32(min)
+				.f32(min + 255.)
+				.bytes(&[0, 1, 2])
+				.align(4);
+		};
+		let mut data = Data(Vec::new());
+		block(&mut data, 0.);
+		let second = data.0.len();
+		block(&mut data, 10.);
+
+		// Five frames in blocks of three, where frame 2 ends the first block
+		// and starts the second.
+		let header = Header {
+			duration: 4.,
+			transform_tracks: 1,
+			float_tracks: 0,
+			frames: 5,
+			max_frames_per_block: 3,
+			mask_size: 4,
+			block_offsets: vec![0, second],
+			float_offsets: Vec::new(),
+		};
+		let animation = animation(data.0, &header).unwrap();
+
+		let x = |time| animation.sample_transform(0, time).unwrap().translation.x;
+		assert_eq!(x(0.), 0.);
+		assert_eq!(x(1.5), 1.5);
+		assert_eq!(x(2.), 10.);
+		assert_eq!(x(3.), 11.);
+		assert_eq!(x(4.), 12.);
+		assert_eq!(x(10.), 12.);
+	}
+}
@@ -0,0 +1,607 @@
+//! Generation of Rust bindings from the struct definitions stored in files.
+
+use std::{
+	collections::{BTreeMap, HashSet},
+	env,
+	fmt::{self, Write as _},
+	fs::{self, File},
+	io::BufReader,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use crate::{
+	document::Document,
+	error::{Error, Result},
+	node::{Definition, FieldKind},
+	tagfile,
+};
+
+/// Representation of inheritance between structs in generated code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Inheritance {
+	/// Each generated struct contains every field of its definition, including
+	/// inherited fields.
+	Flatten,
+	/// Each generated struct contains only the definition's own fields, along
+	/// with a `base` field holding the generated struct of its parent.
+	Compose,
+}
+
+/// Generator of Rust structs for struct definitions.
+///
+/// Every definition added to the generator is emitted as a struct, with field
+/// kinds mapped to Rust types. Definitions that are inherited by other added
+/// definitions additionally produce an `Any*` enum, which is used for
+/// references to that definition such that subclasses are preserved.
+///
+/// By default, generated types derive `FromNode`, and require the `derive`
+/// feature of this crate to be enabled by the consumer.
+#[derive(Debug)]
+pub struct Generator {
+	definitions: BTreeMap<String, Arc<Definition>>,
+	inheritance: Inheritance,
+	derive: bool,
+}
+
+impl Default for Generator {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Generator {
+	/// Create a generator with no definitions, that flattens inheritance.
+	pub fn new() -> Self {
+		Self {
+			definitions: BTreeMap::new(),
+			inheritance: Inheritance::Flatten,
+			derive: true,
+		}
+	}
+
+	/// Set how inheritance between definitions is represented.
+	pub fn inheritance(mut self, inheritance: Inheritance) -> Self {
+		self.inheritance = inheritance;
+		self
+	}
+
+	/// Set whether conversions from nodes are generated. If disabled, only the
+	/// data types themselves are generated.
+	pub fn derive(mut self, derive: bool) -> Self {
+		self.derive = derive;
+		self
+	}
+
+	/// Add every definition in the document. Definitions that have already been
+	/// added must be identical, as only one version of a struct can be generated.
+	pub fn add_document(&mut self, document: &Document) -> Result<()> {
+		for definition in document.definitions() {
+			self.add_definition(definition)?;
+		}
+		Ok(())
+	}
+
+	/// Read the binary tagfile at the specified path, and add its definitions.
+	pub fn add_file(&mut self, path: impl AsRef<Path>) -> Result<()> {
+		let mut reader = BufReader::new(File::open(path)?);
+		let walker = tagfile::read(&mut reader)?;
+		self.add_document(walker.document())
+	}
+
+	/// Add a definition, and the definitions it inherits from. If any of them
+	/// conflicts with an existing definition, none are added.
+	pub fn add_definition(&mut self, definition: &Arc<Definition>) -> Result<()> {
+		let mut added = Vec::new();
+		let mut current = Some(definition);
+		while let Some(definition) = current {
+			if let Some(existing) = self.definitions.get(definition.name()) {
+				// Parents of an existing definition have already been added.
+				match same_layout(existing, definition) {
+					true => break,
+					false => {
+						return Err(Error::Invalid(format!(
+							"Conflicting definitions of {} (v{} and v{}).",
+							definition.name(),
+							existing.version(),
+							definition.version()
+						)))
+					}
+				}
+			}
+			added.push(definition);
+			current = definition.parent();
+		}
+
+		for definition in added {
+			self.definitions
+				.insert(definition.name().to_string(), definition.clone());
+		}
+		Ok(())
+	}
+
+	/// Generate Rust source for all added definitions.
+	pub fn generate(&self) -> String {
+		let mut output = String::new();
+		self.write(&mut output)
+			.expect("writing to a String cannot fail");
+		output
+	}
+
+	/// Generate Rust source for all added definitions, and write it to the
+	/// specified path.
+	pub fn write_to(&self, path: impl AsRef<Path>) -> Result<()> {
+		fs::write(path, self.generate())?;
+		Ok(())
+	}
+
+	fn write(&self, output: &mut String) -> fmt::Result {
+		writeln!(output, "// Generated from file definitions. Do not edit.")?;
+
+		for definition in self.definitions.values() {
+			writeln!(output)?;
+			self.write_struct(output, definition)?;
+
+			let subclasses = self.subclasses(definition.name());
+			if !subclasses.is_empty() {
+				writeln!(output)?;
+				self.write_enum(output, definition, &subclasses)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn write_struct(&self, output: &mut String, definition: &Definition) -> fmt::Result {
+		let name = definition.name();
+		let version = definition.version();
+
+		writeln!(output, "/// `{name}` version {version}.")?;
+		match self.derive {
+			true => {
+				writeln!(output, "#[derive(Clone, Debug, ::mayhem::FromNode)]")?;
+				writeln!(
+					output,
+					"#[mayhem(class = \"{name}\", min_version = {version}, max_version = {version})]"
+				)?;
+			}
+			false => writeln!(output, "#[derive(Clone, Debug)]")?,
+		}
+		writeln!(output, "pub struct {} {{", type_name(name))?;
+
+		let mut idents = HashSet::new();
+		let fields = match (self.inheritance, definition.parent()) {
+			(Inheritance::Compose, Some(parent)) => {
+				let ident = unique_ident("base", &mut idents);
+				if self.derive {
+					writeln!(output, "\t#[mayhem(flatten)]")?;
+				}
+				writeln!(output, "\tpub {ident}: {},", type_name(parent.name()))?;
+				definition.own_fields()
+			}
+			_ => definition.fields(),
+		};
+
+		let mut names = HashSet::new();
+		for field in fields {
+			// Repeated names resolve to the first field, which shadows the rest.
+			if !names.insert(field.name()) {
+				continue;
+			}
+
+			let rust_type = match self.rust_type(field.kind()) {
+				Some(rust_type) => rust_type,
+				None => continue,
+			};
+
+			let ident = unique_ident(&snake_case(field.name()), &mut idents);
+			if self.derive && ident.trim_start_matches("r#") != field.name() {
+				writeln!(output, "\t#[mayhem(rename = \"{}\")]", field.name())?;
+			}
+			writeln!(output, "\tpub {ident}: {rust_type},")?;
+		}
+
+		writeln!(output, "}}")
+	}
+
+	fn write_enum(
+		&self,
+		output: &mut String,
+		definition: &Definition,
+		subclasses: &[&Arc<Definition>],
+	) -> fmt::Result {
+		let name = definition.name();
+		let base = type_name(name);
+		let ident = format!("Any{base}");
+
+		writeln!(output, "/// `{name}`, or any of its subclasses.")?;
+		writeln!(output, "#[derive(Clone, Debug)]")?;
+		writeln!(output, "#[allow(clippy::large_enum_variant)]")?;
+		writeln!(output, "pub enum {ident} {{")?;
+		writeln!(output, "\t{base}({base}),")?;
+		for subclass in subclasses {
+			let variant = type_name(subclass.name());
+			writeln!(output, "\t{variant}({variant}),")?;
+		}
+		writeln!(output, "}}")?;
+
+		if !self.derive {
+			return Ok(());
+		}
+
+		// Nodes of unknown subclasses fall back to the base struct.
+		writeln!(output)?;
+		writeln!(
+			output,
+			"impl ::core::convert::TryFrom<&::mayhem::NodeWalker> for {ident} {{"
+		)?;
+		writeln!(output, "\ttype Error = ::mayhem::Error;")?;
+		writeln!(output)?;
+		writeln!(
+			output,
+			"\tfn try_from(node: &::mayhem::NodeWalker) -> ::core::result::Result<Self, Self::Error> {{"
+		)?;
+		writeln!(output, "\t\tmatch node.name() {{")?;
+		for subclass in subclasses {
+			writeln!(
+				output,
+				"\t\t\t\"{}\" => ::core::convert::TryFrom::try_from(node).map(Self::{}),",
+				subclass.name(),
+				type_name(subclass.name())
+			)?;
+		}
+		writeln!(
+			output,
+			"\t\t\t_ => ::core::convert::TryFrom::try_from(node).map(Self::{base}),"
+		)?;
+		writeln!(output, "\t\t}}")?;
+		writeln!(output, "\t}}")?;
+		writeln!(output, "}}")?;
+		writeln!(output)?;
+		writeln!(
+			output,
+			"impl ::core::convert::TryFrom<::mayhem::NodeWalker> for {ident} {{"
+		)?;
+		writeln!(output, "\ttype Error = ::mayhem::Error;")?;
+		writeln!(output)?;
+		writeln!(
+			output,
+			"\tfn try_from(node: ::mayhem::NodeWalker) -> ::core::result::Result<Self, Self::Error> {{"
+		)?;
+		writeln!(output, "\t\tSelf::try_from(&node)")?;
+		writeln!(output, "\t}}")?;
+		writeln!(output, "}}")?;
+		writeln!(output)?;
+		writeln!(output, "impl ::mayhem::FromField for {ident} {{")?;
+		writeln!(output, "\tfn from_field(")?;
+		writeln!(output, "\t\tnode: &::mayhem::NodeWalker,")?;
+		writeln!(output, "\t\tvalue: &::mayhem::Value,")?;
+		writeln!(
+			output,
+			"\t) -> ::core::result::Result<Self, ::mayhem::Error> {{"
+		)?;
+		writeln!(
+			output,
+			"\t\t::mayhem::__private::from_reference(node, value)"
+		)?;
+		writeln!(output, "\t}}")?;
+		writeln!(output, "}}")
+	}
+
+	/// Get all added definitions that inherit from the named definition.
+	fn subclasses(&self, name: &str) -> Vec<&Arc<Definition>> {
+		self.definitions
+			.values()
+			.filter(|definition| definition.name() != name && definition.is_a(name))
+			.collect()
+	}
+
+	/// Map a field kind to a Rust type. Void kinds hold no value, and have no type.
+	fn rust_type(&self, kind: &FieldKind) -> Option<String> {
+		let rust_type = match kind {
+			FieldKind::Void => return None,
+			FieldKind::Byte => "u8".into(),
+			FieldKind::Float => "f32".into(),
+			FieldKind::Integer => "i32".into(),
+			FieldKind::String => "String".into(),
+			FieldKind::Struct(name) => match self.definitions.contains_key(name) {
+				true => type_name(name),
+				false => "::mayhem::NodeWalker".into(),
+			},
+			FieldKind::Reference(name) => {
+				let target = match (
+					self.definitions.contains_key(name),
+					self.subclasses(name).is_empty(),
+				) {
+					(false, _) => return Some("Option<::mayhem::NodeWalker>".into()),
+					(true, true) => type_name(name),
+					(true, false) => format!("Any{}", type_name(name)),
+				};
+				format!("Option<Box<{target}>>")
+			}
+			FieldKind::Vector(inner) => format!("Vec<{}>", self.rust_type(inner)?),
+			// Vectors may be stored with only three of their floats.
+			FieldKind::Array(inner, 4) if matches!(**inner, FieldKind::Float) => {
+				"::mayhem::math::Vector4".into()
+			}
+			FieldKind::Array(inner, count) => format!("[{}; {count}]", self.rust_type(inner)?),
+		};
+
+		Some(rust_type)
+	}
+}
+
+/// Generate bindings for the tagfiles at the specified paths from a build
+/// script, writing them to the named file within `OUT_DIR`. The generated file
+/// can then be included with
+/// `include!(concat!(env!("OUT_DIR"), "/havok.rs"));`.
+pub fn build_script(
+	paths: impl IntoIterator<Item = impl AsRef<Path>>,
+	file_name: &str,
+) -> Result<()> {
+	let out_dir =
+		env::var_os("OUT_DIR").ok_or_else(|| Error::Invalid("OUT_DIR is not set.".into()))?;
+
+	let mut generator = Generator::new();
+	for path in paths {
+		let path = path.as_ref();
+		println!("cargo:rerun-if-changed={}", path.display());
+		generator.add_file(path)?;
+	}
+
+	generator.write_to(PathBuf::from(out_dir).join(file_name))
+}
+
+fn same_layout(left: &Definition, right: &Definition) -> bool {
+	left.version() == right.version()
+		&& left.fields().len() == right.fields().len()
+		&& left
+			.fields()
+			.iter()
+			.zip(right.fields())
+			.all(|(left, right)| {
+				left.name() == right.name() && left.kind().to_string() == right.kind().to_string()
+			})
+}
+
+/// Convert a struct name to an upper camel case identifier, i.e. `hkaSkeleton`
+/// to `HkaSkeleton`.
+fn type_name(name: &str) -> String {
+	let mut output = String::with_capacity(name.len());
+	for segment in name.split(|character: char| !character.is_ascii_alphanumeric()) {
+		let mut characters = segment.chars();
+		if let Some(first) = characters.next() {
+			output.push(first.to_ascii_uppercase());
+			output.extend(characters);
+		}
+	}
+
+	if !output.starts_with(|character: char| character.is_ascii_alphabetic()) {
+		output.insert(0, '_');
+	}
+	output
+}
+
+/// Convert a field name to a snake case identifier, i.e. `parentIndices` to
+/// `parent_indices`.
+fn snake_case(name: &str) -> String {
+	let mut output = String::with_capacity(name.len() + 4);
+	let mut previous_lower = false;
+	for character in name.chars() {
+		if !character.is_ascii_alphanumeric() {
+			output.push('_');
+			previous_lower = false;
+			continue;
+		}
+
+		if character.is_ascii_uppercase() && previous_lower {
+			output.push('_');
+		}
+		output.push(character.to_ascii_lowercase());
+		previous_lower = character.is_ascii_lowercase() || character.is_ascii_digit();
+	}
+
+	if !output.starts_with(|character: char| character.is_ascii_alphabetic() || character == '_') {
+		output.insert(0, '_');
+	}
+	output
+}
+
+const KEYWORDS: &[&str] = &[
+	"abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+	"else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+	"loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+	"static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+	"virtual", "where", "while", "yield",
+];
+
+/// Build an identifier that has not yet been used, escaping keywords.
+fn unique_ident(name: &str, used: &mut HashSet<String>) -> String {
+	let mut ident = match name {
+		// These keywords cannot be used as raw identifiers.
+		"crate" | "self" | "super" | "_" => format!("{name}_"),
+		_ => name.to_string(),
+	};
+	while used.contains(&ident) {
+		ident.push('_');
+	}
+	used.insert(ident.clone());
+
+	match KEYWORDS.contains(&ident.as_str()) {
+		true => format!("r#{ident}"),
+		false => ident,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		math::Vector4,
+		node::FieldKind,
+		testing::{definition, node, versioned_definition, walker},
+		value::Value,
+		walker::NodeWalker,
+	};
+
+	use super::{Generator, Inheritance};
+
+	fn document() -> NodeWalker {
+		let object = definition("hkReferencedObject", None, vec![]);
+		let animation = versioned_definition(
+			"hkaAnimation",
+			3,
+			Some(&object),
+			vec![
+				("type", FieldKind::Integer),
+				("duration", FieldKind::Float),
+				("extractedMotion", FieldKind::Reference("hkaMotion".into())),
+			],
+		);
+		let spline = definition(
+			"hkaSplineCompressedAnimation",
+			Some(&animation),
+			vec![
+				("data", FieldKind::Vector(FieldKind::Byte.into())),
+				("pad", FieldKind::Void),
+			],
+		);
+		let container = definition(
+			"hkaAnimationContainer",
+			Some(&object),
+			vec![
+				(
+					"animations",
+					FieldKind::Vector(FieldKind::Reference("hkaAnimation".into()).into()),
+				),
+				("transform", FieldKind::Array(FieldKind::Float.into(), 4)),
+			],
+		);
+
+		walker(vec![
+			node(
+				&container,
+				vec![
+					Some(Value::Vector(vec![Value::Node(1)])),
+					Some(Value::Vector(
+						[1., 2., 3.].into_iter().map(Value::F32).collect(),
+					)),
+				],
+			),
+			node(
+				&spline,
+				vec![
+					Some(Value::I32(1)),
+					Some(Value::F32(2.)),
+					None,
+					Some(Value::Vector(vec![Value::U8(7)])),
+					None,
+				],
+			),
+		])
+	}
+
+	fn generator(inheritance: Inheritance) -> Generator {
+		let mut generator = Generator::new().inheritance(inheritance);
+		generator.add_document(document().document()).unwrap();
+		generator
+	}
+
+	#[test]
+	fn flatten() {
+		let output = generator(Inheritance::Flatten).generate();
+
+		assert!(output.contains(concat!(
+			"/// `hkaAnimation` version 3.\n",
+			"#[derive(Clone, Debug, ::mayhem::FromNode)]\n",
+			"#[mayhem(class = \"hkaAnimation\", min_version = 3, max_version = 3)]\n",
+			"pub struct HkaAnimation {\n",
+			"\tpub r#type: i32,\n",
+			"\tpub duration: f32,\n",
+			"\t#[mayhem(rename = \"extractedMotion\")]\n",
+			"\tpub extracted_motion: Option<::mayhem::NodeWalker>,\n",
+			"}\n",
+		)));
+		assert!(output.contains(concat!(
+			"pub struct HkaSplineCompressedAnimation {\n",
+			"\tpub r#type: i32,\n",
+			"\tpub duration: f32,\n",
+			"\t#[mayhem(rename = \"extractedMotion\")]\n",
+			"\tpub extracted_motion: Option<::mayhem::NodeWalker>,\n",
+			"\tpub data: Vec<u8>,\n",
+			"}\n",
+		)));
+		assert!(output.contains(concat!(
+			"pub struct HkaAnimationContainer {\n",
+			"\tpub animations: Vec<Option<Box<AnyHkaAnimation>>>,\n",
+			"\tpub transform: ::mayhem::math::Vector4,\n",
+			"}\n",
+		)));
+		assert!(output.contains(concat!(
+			"pub enum AnyHkaAnimation {\n",
+			"\tHkaAnimation(HkaAnimation),\n",
+			"\tHkaSplineCompressedAnimation(HkaSplineCompressedAnimation),\n",
+			"}\n",
+		)));
+		assert!(output.contains(
+			"\"hkaSplineCompressedAnimation\" => ::core::convert::TryFrom::try_from(node).map(Self::HkaSplineCompressedAnimation),"
+		));
+		assert!(output.contains("pub enum AnyHkReferencedObject {"));
+	}
+
+	/// Output of the generator for the test document, such that it is compiled
+	/// along with the tests.
+	#[allow(dead_code)]
+	mod generated {
+		include!("codegen/generated.rs");
+	}
+
+	#[test]
+	fn compiles() {
+		assert_eq!(
+			generator(Inheritance::Flatten).generate(),
+			include_str!("codegen/generated.rs"),
+			"Generated output has changed, and src/codegen/generated.rs must be updated."
+		);
+
+		let container = generated::HkaAnimationContainer::try_from(&document()).unwrap();
+		assert_eq!(container.transform, Vector4::new(1., 2., 3., 0.));
+		match container.animations[0].as_deref() {
+			Some(generated::AnyHkaAnimation::HkaSplineCompressedAnimation(spline)) => {
+				assert_eq!(spline.duration, 2.);
+				assert_eq!(spline.data, [7]);
+			}
+			other => panic!("Expected a spline compressed animation, got {other:?}."),
+		}
+	}
+
+	#[test]
+	fn compose() {
+		let output = generator(Inheritance::Compose).derive(false).generate();
+
+		assert!(output.contains(concat!(
+			"/// `hkaSplineCompressedAnimation` version 0.\n",
+			"#[derive(Clone, Debug)]\n",
+			"pub struct HkaSplineCompressedAnimation {\n",
+			"\tpub base: HkaAnimation,\n",
+			"\tpub data: Vec<u8>,\n",
+			"}\n",
+		)));
+		assert!(!output.contains("FromNode") && !output.contains("impl"));
+	}
+
+	#[test]
+	fn conflicting_versions() {
+		let mut generator = generator(Inheritance::Flatten);
+		let animation = definition("hkaAnimation", None, vec![]);
+		let error = generator.add_definition(&animation).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Invalid: Conflicting definitions of hkaAnimation (v3 and v0)."
+		);
+
+		// Nothing is added when an inherited definition conflicts.
+		let subclass = definition("hkaQuantizedAnimation", Some(&animation), vec![]);
+		assert!(generator.add_definition(&subclass).is_err());
+		assert!(!generator.definitions.contains_key("hkaQuantizedAnimation"));
+	}
+}
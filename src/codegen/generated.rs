@@ -0,0 +1,112 @@
+// Generated from file definitions. Do not edit.
+
+/// `hkReferencedObject` version 0.
+#[derive(Clone, Debug, ::mayhem::FromNode)]
+#[mayhem(class = "hkReferencedObject", min_version = 0, max_version = 0)]
+pub struct HkReferencedObject {
+}
+
+/// `hkReferencedObject`, or any of its subclasses.
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum AnyHkReferencedObject {
+	HkReferencedObject(HkReferencedObject),
+	HkaAnimation(HkaAnimation),
+	HkaAnimationContainer(HkaAnimationContainer),
+	HkaSplineCompressedAnimation(HkaSplineCompressedAnimation),
+}
+
+impl ::core::convert::TryFrom<&::mayhem::NodeWalker> for AnyHkReferencedObject {
+	type Error = ::mayhem::Error;
+
+	fn try_from(node: &::mayhem::NodeWalker) -> ::core::result::Result<Self, Self::Error> {
+		match node.name() {
+			"hkaAnimation" => ::core::convert::TryFrom::try_from(node).map(Self::HkaAnimation),
+			"hkaAnimationContainer" => ::core::convert::TryFrom::try_from(node).map(Self::HkaAnimationContainer),
+			"hkaSplineCompressedAnimation" => ::core::convert::TryFrom::try_from(node).map(Self::HkaSplineCompressedAnimation),
+			_ => ::core::convert::TryFrom::try_from(node).map(Self::HkReferencedObject),
+		}
+	}
+}
+
+impl ::core::convert::TryFrom<::mayhem::NodeWalker> for AnyHkReferencedObject {
+	type Error = ::mayhem::Error;
+
+	fn try_from(node: ::mayhem::NodeWalker) -> ::core::result::Result<Self, Self::Error> {
+		Self::try_from(&node)
+	}
+}
+
+impl ::mayhem::FromField for AnyHkReferencedObject {
+	fn from_field(
+		node: &::mayhem::NodeWalker,
+		value: &::mayhem::Value,
+	) -> ::core::result::Result<Self, ::mayhem::Error> {
+		::mayhem::__private::from_reference(node, value)
+	}
+}
+
+/// `hkaAnimation` version 3.
+#[derive(Clone, Debug, ::mayhem::FromNode)]
+#[mayhem(class = "hkaAnimation", min_version = 3, max_version = 3)]
+pub struct HkaAnimation {
+	pub r#type: i32,
+	pub duration: f32,
+	#[mayhem(rename = "extractedMotion")]
+	pub extracted_motion: Option<::mayhem::NodeWalker>,
+}
+
+/// `hkaAnimation`, or any of its subclasses.
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum AnyHkaAnimation {
+	HkaAnimation(HkaAnimation),
+	HkaSplineCompressedAnimation(HkaSplineCompressedAnimation),
+}
+
+impl ::core::convert::TryFrom<&::mayhem::NodeWalker> for AnyHkaAnimation {
+	type Error = ::mayhem::Error;
+
+	fn try_from(node: &::mayhem::NodeWalker) -> ::core::result::Result<Self, Self::Error> {
+		match node.name() {
+			"hkaSplineCompressedAnimation" => ::core::convert::TryFrom::try_from(node).map(Self::HkaSplineCompressedAnimation),
+			_ => ::core::convert::TryFrom::try_from(node).map(Self::HkaAnimation),
+		}
+	}
+}
+
+impl ::core::convert::TryFrom<::mayhem::NodeWalker> for AnyHkaAnimation {
+	type Error = ::mayhem::Error;
+
+	fn try_from(node: ::mayhem::NodeWalker) -> ::core::result::Result<Self, Self::Error> {
+		Self::try_from(&node)
+	}
+}
+
+impl ::mayhem::FromField for AnyHkaAnimation {
+	fn from_field(
+		node: &::mayhem::NodeWalker,
+		value: &::mayhem::Value,
+	) -> ::core::result::Result<Self, ::mayhem::Error> {
+		::mayhem::__private::from_reference(node, value)
+	}
+}
+
+/// `hkaAnimationContainer` version 0.
+#[derive(Clone, Debug, ::mayhem::FromNode)]
+#[mayhem(class = "hkaAnimationContainer", min_version = 0, max_version = 0)]
+pub struct HkaAnimationContainer {
+	pub animations: Vec<Option<Box<AnyHkaAnimation>>>,
+	pub transform: ::mayhem::math::Vector4,
+}
+
+/// `hkaSplineCompressedAnimation` version 0.
+#[derive(Clone, Debug, ::mayhem::FromNode)]
+#[mayhem(class = "hkaSplineCompressedAnimation", min_version = 0, max_version = 0)]
+pub struct HkaSplineCompressedAnimation {
+	pub r#type: i32,
+	pub duration: f32,
+	#[mayhem(rename = "extractedMotion")]
+	pub extracted_motion: Option<::mayhem::NodeWalker>,
+	pub data: Vec<u8>,
+}
@@ -0,0 +1,397 @@
+use std::{borrow::Cow, fmt::Display};
+
+use serde::{
+	de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor},
+	forward_to_deserialize_any, Deserializer,
+};
+
+use crate::{error::Error, node::Field, value::Value, walker::NodeWalker};
+
+impl de::Error for Error {
+	fn custom<T: Display>(message: T) -> Self {
+		Self::Invalid(message.to_string())
+	}
+}
+
+/// Nodes deserialize as maps of their fields. Fields that were not stored are
+/// provided with their default value where possible, see
+/// [`FieldLookup::NotStored`](crate::FieldLookup::NotStored).
+///
+/// References are followed during deserialization. A reference back to a node
+/// that is still being deserialized, i.e. a cycle in the graph, is an error.
+impl<'de> Deserializer<'de> for &NodeWalker {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		NodeDeserializer {
+			walker: self,
+			parent: None,
+		}
+		.deserialize_any(visitor)
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+		identifier ignored_any
+	}
+}
+
+/// Indices of the nodes currently being deserialized, innermost first.
+struct Ancestors<'a> {
+	index: usize,
+	parent: Option<&'a Ancestors<'a>>,
+}
+
+impl Ancestors<'_> {
+	fn contains(&self, index: usize) -> bool {
+		self.index == index || self.parent.is_some_and(|parent| parent.contains(index))
+	}
+}
+
+struct NodeDeserializer<'a> {
+	walker: &'a NodeWalker,
+	parent: Option<&'a Ancestors<'a>>,
+}
+
+impl<'de> Deserializer<'de> for NodeDeserializer<'_> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let index = self.walker.index();
+		if self.parent.is_some_and(|parent| parent.contains(index)) {
+			return Err(Error::Invalid(format!(
+				"Cyclic reference to node {index} ({}).",
+				self.walker.name()
+			)));
+		}
+
+		let ancestors = Ancestors {
+			index,
+			parent: self.parent,
+		};
+		visitor.visit_map(NodeAccess {
+			walker: self.walker,
+			ancestors: &ancestors,
+			fields: self.walker.definition().fields().iter(),
+			value: None,
+		})
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	// Ignored nodes are not entered, so skipping a back reference is not a cycle.
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+		identifier
+	}
+}
+
+struct NodeAccess<'a, I> {
+	walker: &'a NodeWalker,
+	ancestors: &'a Ancestors<'a>,
+	fields: I,
+	value: Option<Cow<'a, Value>>,
+}
+
+impl<'de, 'a, I> MapAccess<'de> for NodeAccess<'a, I>
+where
+	I: Iterator<Item = &'a Field>,
+{
+	type Error = Error;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(
+		&mut self,
+		seed: K,
+	) -> Result<Option<K::Value>, Self::Error> {
+		// Skip fields that have no value, and no default to fall back to.
+		for field in self.fields.by_ref() {
+			if let Some(value) = self.walker.field_or_default(field.name()) {
+				self.value = Some(value);
+				return seed.deserialize(field.name().into_deserializer()).map(Some);
+			}
+		}
+
+		Ok(None)
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(
+		&mut self,
+		seed: V,
+	) -> Result<V::Value, Self::Error> {
+		let value = self
+			.value
+			.take()
+			.ok_or_else(|| Error::Invalid("Value requested before key.".into()))?;
+		seed.deserialize(ValueDeserializer {
+			walker: self.walker,
+			ancestors: self.ancestors,
+			value: &value,
+		})
+	}
+}
+
+struct ValueDeserializer<'a> {
+	walker: &'a NodeWalker,
+	ancestors: &'a Ancestors<'a>,
+	value: &'a Value,
+}
+
+impl<'a> ValueDeserializer<'a> {
+	fn node(&self) -> Result<Option<NodeWalker>, Error> {
+		self.walker.resolve(self.value)
+	}
+
+	fn node_deserializer<'b>(&'b self, walker: &'b NodeWalker) -> NodeDeserializer<'b> {
+		NodeDeserializer {
+			walker,
+			parent: Some(self.ancestors),
+		}
+	}
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'_> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.value {
+			Value::U8(value) => visitor.visit_u8(*value),
+			Value::I32(value) => visitor.visit_i32(*value),
+			Value::F32(value) => visitor.visit_f32(*value),
+			Value::String(value) => visitor.visit_str(value),
+			Value::Node(..) => match self.node()? {
+				Some(node) => self.node_deserializer(&node).deserialize_any(visitor),
+				None => visitor.visit_none(),
+			},
+			Value::Vector(values) => visitor.visit_seq(VectorAccess {
+				walker: self.walker,
+				ancestors: self.ancestors,
+				values: values.iter(),
+			}),
+		}
+	}
+
+	// Havok booleans are stored as bytes.
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.value {
+			Value::U8(value) => visitor.visit_bool(*value != 0),
+			_ => self.deserialize_any(visitor),
+		}
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.value {
+			Value::Node(..) => match self.node()? {
+				Some(node) => visitor.visit_some(self.node_deserializer(&node)),
+				None => visitor.visit_none(),
+			},
+			_ => visitor.visit_some(self),
+		}
+	}
+
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_byte_buf(visitor)
+	}
+
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let bytes = self
+			.value
+			.as_vector()
+			.and_then(|values| values.iter().map(|value| value.as_u8().copied()).collect());
+		match bytes {
+			Some(bytes) => visitor.visit_byte_buf(bytes),
+			None => self.deserialize_any(visitor),
+		}
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+
+	forward_to_deserialize_any! {
+		i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		unit unit_struct seq tuple tuple_struct map struct enum identifier
+	}
+}
+
+struct VectorAccess<'a, I> {
+	walker: &'a NodeWalker,
+	ancestors: &'a Ancestors<'a>,
+	values: I,
+}
+
+impl<'de, 'a, I> SeqAccess<'de> for VectorAccess<'a, I>
+where
+	I: ExactSizeIterator<Item = &'a Value>,
+{
+	type Error = Error;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, Self::Error> {
+		self.values
+			.next()
+			.map(|value| {
+				seed.deserialize(ValueDeserializer {
+					walker: self.walker,
+					ancestors: self.ancestors,
+					value,
+				})
+			})
+			.transpose()
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.values.len())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::rc::Rc;
+
+	use serde::Deserialize;
+
+	use crate::{
+		node::FieldKind,
+		testing::{node, skeleton, walker},
+		value::{Value, NULL_NODE},
+		walker::NodeWalker,
+	};
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	struct Skeleton {
+		name: String,
+		parent_indices: Vec<i32>,
+		reference_pose: Vec<[f32; 4]>,
+		bones: Vec<Rc<Bone>>,
+		parent: Option<Box<Skeleton>>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	struct Bone {
+		name: String,
+		lock_translation: bool,
+	}
+
+	#[test]
+	fn deserialize() {
+		let (skeleton, bone) = skeleton(
+			0,
+			vec![
+				(
+					"referencePose",
+					FieldKind::Vector(FieldKind::Array(FieldKind::Float.into(), 4).into()),
+				),
+				("parent", FieldKind::Reference("hkaSkeleton".into())),
+			],
+		);
+		let walker = walker(vec![
+			node(
+				&skeleton,
+				vec![
+					Some(Value::String("root".into())),
+					Some(Value::Vector(vec![Value::I32(-1)])),
+					Some(Value::Vector(vec![Value::Node(1)])),
+					Some(Value::Vector(vec![Value::Vector(vec![
+						Value::F32(0.),
+						Value::F32(1.),
+						Value::F32(2.),
+						Value::F32(3.),
+					])])),
+					Some(Value::Node(NULL_NODE)),
+				],
+			),
+			// Omitted fields should be filled with defaults.
+			node(&bone, vec![Some(Value::String("j_kosi".into())), None]),
+		]);
+
+		let skeleton = walker.deserialize::<Skeleton>().unwrap();
+		assert_eq!(skeleton.name, "root");
+		assert_eq!(skeleton.parent_indices, [-1]);
+		assert_eq!(skeleton.reference_pose, [[0., 1., 2., 3.]]);
+		assert_eq!(skeleton.bones[0].name, "j_kosi");
+		assert!(!skeleton.bones[0].lock_translation);
+		assert!(skeleton.parent.is_none());
+	}
+
+	fn cyclic() -> NodeWalker {
+		let (skeleton, _) = skeleton(
+			0,
+			vec![("parent", FieldKind::Reference("hkaSkeleton".into()))],
+		);
+		walker(vec![node(
+			&skeleton,
+			vec![
+				Some(Value::String("root".into())),
+				None,
+				None,
+				Some(Value::Node(0)),
+			],
+		)])
+	}
+
+	#[test]
+	fn cycle() {
+		#[derive(Debug, Deserialize)]
+		#[allow(dead_code)]
+		struct Skeleton {
+			name: String,
+			parent: Option<Box<Skeleton>>,
+		}
+
+		assert_eq!(
+			cyclic().deserialize::<Skeleton>().unwrap_err().to_string(),
+			"Invalid: Cyclic reference to node 0 (hkaSkeleton)."
+		);
+	}
+
+	#[test]
+	fn ignored_cycle() {
+		// The back reference is skipped, and never resolved.
+		#[derive(Debug, Deserialize)]
+		struct Skeleton {
+			name: String,
+		}
+
+		assert_eq!(cyclic().deserialize::<Skeleton>().unwrap().name, "root");
+	}
+}
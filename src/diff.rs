@@ -0,0 +1,320 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{value::Value, walker::NodeWalker};
+
+/// Options controlling a structural comparison between nodes.
+#[derive(Clone, Debug, Default)]
+pub struct DiffOptions {
+	epsilon: f32,
+}
+
+impl DiffOptions {
+	/// Create options for an exact comparison.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the maximum absolute difference between two floats that will still be
+	/// considered equal.
+	pub fn epsilon(mut self, epsilon: f32) -> Self {
+		self.epsilon = epsilon;
+		self
+	}
+}
+
+/// A single difference found by a structural comparison.
+#[derive(Clone, Debug)]
+pub struct Difference {
+	/// Path to the differing item from the compared nodes, i.e. `bones[3].name`.
+	/// An empty path refers to the compared nodes themselves.
+	pub path: String,
+	/// The kind of difference found.
+	pub kind: DifferenceKind,
+}
+
+/// Kind of difference found by a structural comparison.
+#[derive(Clone, Debug)]
+pub enum DifferenceKind {
+	/// The nodes are of different structs, or struct versions.
+	Class {
+		/// Struct name and version on the left.
+		left: (String, i32),
+		/// Struct name and version on the right.
+		right: (String, i32),
+	},
+	/// The field only has a value on the left.
+	OnlyLeft,
+	/// The field only has a value on the right.
+	OnlyRight,
+	/// The values differ.
+	Value {
+		/// Value on the left.
+		left: Value,
+		/// Value on the right.
+		right: Value,
+	},
+	/// The vectors differ in length.
+	Length {
+		/// Length on the left.
+		left: usize,
+		/// Length on the right.
+		right: usize,
+	},
+	/// The references differ in shape - one is null and the other is not, or a
+	/// node is referenced where a different node was previously paired with it.
+	Reference,
+}
+
+impl fmt::Display for Difference {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let path = match self.path.is_empty() {
+			true => "<root>",
+			false => &self.path,
+		};
+
+		match &self.kind {
+			DifferenceKind::Class { left, right } => write!(
+				f,
+				"{path}: class {} (v{}) != {} (v{})",
+				left.0, left.1, right.0, right.1
+			),
+			DifferenceKind::OnlyLeft => write!(f, "{path}: only on left"),
+			DifferenceKind::OnlyRight => write!(f, "{path}: only on right"),
+			DifferenceKind::Value { left, right } => write!(f, "{path}: {left:?} != {right:?}"),
+			DifferenceKind::Length { left, right } => write!(f, "{path}: length {left} != {right}"),
+			DifferenceKind::Reference => write!(f, "{path}: reference mismatch"),
+		}
+	}
+}
+
+pub fn diff(left: &NodeWalker, right: &NodeWalker, options: &DiffOptions) -> Vec<Difference> {
+	let mut differ = Differ {
+		options,
+		pairs: HashMap::new(),
+		reverse_pairs: HashMap::new(),
+		differences: Vec::new(),
+	};
+	differ.diff_node(left, right, String::new());
+	differ.differences
+}
+
+struct Differ<'a> {
+	options: &'a DiffOptions,
+	// Pairings between node indices on the left and right, used to detect
+	// revisits and cycles, as well as mismatched reference topology.
+	pairs: HashMap<usize, usize>,
+	reverse_pairs: HashMap<usize, usize>,
+	differences: Vec<Difference>,
+}
+
+impl Differ<'_> {
+	fn diff_node(&mut self, left: &NodeWalker, right: &NodeWalker, path: String) {
+		match (
+			self.pairs.get(&left.index()),
+			self.reverse_pairs.get(&right.index()),
+		) {
+			// Already compared (or being compared) against each other.
+			(Some(&paired), _) if paired == right.index() => return,
+			// One of the nodes has been paired with a different node.
+			(Some(_), _) | (_, Some(_)) => {
+				return self.push(path, DifferenceKind::Reference);
+			}
+			(None, None) => {
+				self.pairs.insert(left.index(), right.index());
+				self.reverse_pairs.insert(right.index(), left.index());
+			}
+		}
+
+		if left.name() != right.name() || left.version() != right.version() {
+			self.push(
+				path.clone(),
+				DifferenceKind::Class {
+					left: (left.name().to_string(), left.version()),
+					right: (right.name().to_string(), right.version()),
+				},
+			);
+		}
+
+		// Fields are matched by name, so that structs with differing layouts can
+		// still be compared meaningfully.
+		let left_fields = left.definition().fields();
+		let right_only = right
+			.definition()
+			.fields()
+			.iter()
+			.filter(|field| left.definition().slot(field.name()).is_none());
+		let names = left_fields
+			.iter()
+			.chain(right_only)
+			.map(|field| field.name());
+
+		for name in names {
+			let field_path = match path.is_empty() {
+				true => name.to_string(),
+				false => format!("{path}.{name}"),
+			};
+
+			// Fields that were not stored are compared as their default value, see
+			// `FieldLookup::NotStored`.
+			match (left.field_or_default(name), right.field_or_default(name)) {
+				(None, None) => {}
+				(Some(_), None) => self.push(field_path, DifferenceKind::OnlyLeft),
+				(None, Some(_)) => self.push(field_path, DifferenceKind::OnlyRight),
+				(Some(left_value), Some(right_value)) => {
+					self.diff_value(left, &left_value, right, &right_value, field_path)
+				}
+			}
+		}
+	}
+
+	fn diff_value(
+		&mut self,
+		left_walker: &NodeWalker,
+		left: &Value,
+		right_walker: &NodeWalker,
+		right: &Value,
+		path: String,
+	) {
+		match (left, right) {
+			(Value::F32(left_float), Value::F32(right_float)) => {
+				let equal = left_float.to_bits() == right_float.to_bits()
+					|| (left_float - right_float).abs() <= self.options.epsilon;
+				if !equal {
+					self.push_value(path, left, right);
+				}
+			}
+
+			(Value::Node(..), Value::Node(..)) => {
+				match (left_walker.resolve(left), right_walker.resolve(right)) {
+					(Ok(Some(left_node)), Ok(Some(right_node))) => {
+						self.diff_node(&left_node, &right_node, path)
+					}
+					(Ok(None), Ok(None)) => {}
+					_ => self.push(path, DifferenceKind::Reference),
+				}
+			}
+
+			(Value::Vector(left_values), Value::Vector(right_values)) => {
+				if left_values.len() != right_values.len() {
+					self.push(
+						path.clone(),
+						DifferenceKind::Length {
+							left: left_values.len(),
+							right: right_values.len(),
+						},
+					);
+				}
+
+				for (index, (left, right)) in left_values.iter().zip(right_values).enumerate() {
+					self.diff_value(
+						left_walker,
+						left,
+						right_walker,
+						right,
+						format!("{path}[{index}]"),
+					);
+				}
+			}
+
+			(left, right) => {
+				if left != right {
+					self.push_value(path, left, right)
+				}
+			}
+		}
+	}
+
+	fn push(&mut self, path: String, kind: DifferenceKind) {
+		self.differences.push(Difference { path, kind })
+	}
+
+	fn push_value(&mut self, path: String, left: &Value, right: &Value) {
+		self.push(
+			path,
+			DifferenceKind::Value {
+				left: left.clone(),
+				right: right.clone(),
+			},
+		)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, walker},
+		value::Value,
+	};
+
+	use super::{DiffOptions, DifferenceKind};
+
+	#[test]
+	fn diff() {
+		let base = definition(
+			"base",
+			None,
+			vec![
+				("name", FieldKind::String),
+				("count", FieldKind::Integer),
+				("values", FieldKind::Vector(FieldKind::Float.into())),
+				("next", FieldKind::Reference("base".into())),
+			],
+		);
+		let floats =
+			|values: &[f32]| Value::Vector(values.iter().map(|v| Value::F32(*v)).collect());
+
+		let left = walker(vec![
+			node(
+				&base,
+				vec![
+					Some(Value::String("a".into())),
+					Some(Value::I32(0)),
+					Some(floats(&[1., 2.])),
+					Some(Value::Node(1)),
+				],
+			),
+			node(
+				&base,
+				vec![None, Some(Value::I32(1)), None, Some(Value::Node(0))],
+			),
+		]);
+		let right = walker(vec![
+			node(
+				&base,
+				vec![
+					Some(Value::String("a".into())),
+					// Omitted, but equal to the default.
+					None,
+					Some(floats(&[1.05, 2., 3.])),
+					Some(Value::Node(1)),
+				],
+			),
+			node(
+				&base,
+				vec![None, Some(Value::I32(2)), None, Some(Value::Node(0))],
+			),
+		]);
+
+		let differences = left.diff(&right, &DiffOptions::new().epsilon(0.1));
+		let summary = differences
+			.iter()
+			.map(|difference| difference.to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			summary,
+			["values: length 2 != 3", "next.count: I32(1) != I32(2)"]
+		);
+
+		let differences = left.diff(&right, &DiffOptions::new());
+		assert!(matches!(
+			differences[1],
+			super::Difference {
+				ref path,
+				kind: DifferenceKind::Value { .. }
+			} if path == "values[0]"
+		));
+
+		assert!(left.diff(&left, &DiffOptions::new()).is_empty());
+	}
+}
@@ -0,0 +1,151 @@
+use std::{
+	collections::HashMap,
+	sync::{Arc, OnceLock},
+};
+
+use crate::{
+	node::{Definition, Node},
+	value::Value,
+};
+
+/// Collection of nodes decoded from a single file, shared between walkers.
+#[derive(Debug)]
+pub struct Document {
+	pub(crate) root: usize,
+	pub(crate) nodes: Vec<Node>,
+	pub(crate) definitions: Vec<Arc<Definition>>,
+
+	// Indexes
+	classes: HashMap<String, Vec<usize>>,
+	referrers: OnceLock<Vec<Vec<Referrer>>>,
+}
+
+/// A single reference to a node from a field of another node.
+#[derive(Debug)]
+pub(crate) struct Referrer {
+	/// Index of the referring node.
+	pub node: usize,
+	/// Index of the referring field within the node's flattened fields.
+	pub field: usize,
+	/// Index of the reference within the field's value, if it is a vector.
+	pub element: Option<usize>,
+}
+
+impl Document {
+	pub(crate) fn new(root: usize, nodes: Vec<Node>, definitions: Vec<Arc<Definition>>) -> Self {
+		// Build an index of node indices by their exact struct name. Nodes are
+		// visited in order, so each list of indices is sorted.
+		let mut classes = HashMap::<String, Vec<usize>>::new();
+		for (index, node) in nodes.iter().enumerate() {
+			classes
+				.entry(node.definition.name.clone())
+				.or_default()
+				.push(index);
+		}
+
+		Self {
+			root,
+			nodes,
+			definitions,
+			classes,
+			referrers: OnceLock::new(),
+		}
+	}
+
+	/// Get the index of the document's root node.
+	pub fn root_index(&self) -> usize {
+		self.root
+	}
+
+	/// Get the number of nodes in the document.
+	pub fn node_count(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// Get all struct definitions declared by the document, in declaration order.
+	pub fn definitions(&self) -> &[Arc<Definition>] {
+		&self.definitions
+	}
+
+	/// Get the struct definition with the specified name, if it was declared.
+	pub fn definition(&self, name: &str) -> Option<&Arc<Definition>> {
+		self.definitions
+			.iter()
+			.find(|definition| definition.name == name)
+	}
+
+	/// Get all references to the specified node. The reverse reference index is
+	/// relatively expensive to build, and will only be built on first use.
+	pub(crate) fn referrers(&self, index: usize) -> &[Referrer] {
+		self.referrers
+			.get_or_init(|| self.build_referrers())
+			.get(index)
+			.map_or(&[], Vec::as_slice)
+	}
+
+	fn build_referrers(&self) -> Vec<Vec<Referrer>> {
+		let mut referrers = (0..self.nodes.len())
+			.map(|_| Vec::new())
+			.collect::<Vec<_>>();
+
+		for (node_index, node) in self.nodes.iter().enumerate() {
+			let values = node
+				.iter_fields()
+				.enumerate()
+				.filter_map(|(field, (_, value))| value.map(|value| (field, value)));
+
+			for (field, value) in values {
+				// References may only be stored directly in a field, or as the elements
+				// of a vector field - nested vectors are only used for arrays of floats.
+				let targets = match value {
+					Value::Node(target) => vec![(*target, None)],
+					Value::Vector(elements) => elements
+						.iter()
+						.enumerate()
+						.filter_map(|(element, value)| {
+							value.as_node().map(|target| (*target, Some(element)))
+						})
+						.collect(),
+					_ => continue,
+				};
+
+				// Out of range targets (i.e. null references) are not recorded.
+				for (target, element) in targets {
+					if let Some(entry) = referrers.get_mut(target) {
+						entry.push(Referrer {
+							node: node_index,
+							field,
+							element,
+						});
+					}
+				}
+			}
+		}
+
+		referrers
+	}
+
+	/// Get the indices of all nodes of the specified struct, in ascending order.
+	pub(crate) fn class_indices(&self, name: &str, include_subclasses: bool) -> Vec<usize> {
+		if !include_subclasses {
+			return self.classes.get(name).cloned().unwrap_or_default();
+		}
+
+		// Subclasses are resolved via the definitions, rather than walking every
+		// node's inheritance chain.
+		let mut indices = self
+			.definitions
+			.iter()
+			.filter(|definition| definition.is_a(name))
+			.filter_map(|definition| self.classes.get(&definition.name))
+			.flatten()
+			.copied()
+			.collect::<Vec<_>>();
+
+		// Multiple definitions may share a name, and each contributes a separately
+		// sorted run - normalise the result.
+		indices.sort_unstable();
+		indices.dedup();
+		indices
+	}
+}
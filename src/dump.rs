@@ -0,0 +1,268 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{enums::Registry, value::Value, walker::NodeWalker};
+
+const INDENT: &str = "  ";
+
+/// Human-readable tree rendering of a node, and the nodes reachable from it.
+///
+/// Each node is rendered in full the first time it is encountered. Subsequent
+/// references to the same node are rendered as a back-reference marker, i.e.
+/// `#12`, which also guards against cycles in the node graph.
+///
+/// Fields with a known enum definition are rendered by name, see [`Registry`].
+#[derive(Debug)]
+pub struct Dump<'a> {
+	walker: &'a NodeWalker,
+	depth: Option<usize>,
+	registry: Option<&'a Registry>,
+}
+
+impl<'a> Dump<'a> {
+	pub(crate) fn new(walker: &'a NodeWalker) -> Self {
+		Self {
+			walker,
+			depth: None,
+			registry: Some(Registry::havok()),
+		}
+	}
+
+	/// Limit the number of levels of nodes that will be expanded below the
+	/// starting node. Nodes past this depth will be rendered without fields.
+	pub fn depth(mut self, depth: usize) -> Self {
+		self.depth = Some(depth);
+		self
+	}
+
+	/// Set the registry used to render enum fields by name. Defaults to
+	/// [`Registry::havok`]. If `None`, enum fields are rendered as integers.
+	pub fn registry(mut self, registry: Option<&'a Registry>) -> Self {
+		self.registry = registry;
+		self
+	}
+}
+
+impl fmt::Display for Dump<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut dumper = Dumper {
+			formatter: f,
+			max_depth: self.depth,
+			registry: self.registry,
+			level: 0,
+			visited: HashSet::new(),
+		};
+		dumper.write_node(self.walker, 0)?;
+		writeln!(dumper.formatter)
+	}
+}
+
+struct Dumper<'a, 'b> {
+	formatter: &'a mut fmt::Formatter<'b>,
+	max_depth: Option<usize>,
+	registry: Option<&'a Registry>,
+	level: usize,
+	visited: HashSet<usize>,
+}
+
+impl Dumper<'_, '_> {
+	fn write_node(&mut self, walker: &NodeWalker, depth: usize) -> fmt::Result {
+		let index = walker.index();
+		if self.visited.contains(&index) {
+			return write!(self.formatter, "#{index}");
+		}
+
+		write!(
+			self.formatter,
+			"#{index} {} (v{})",
+			walker.name(),
+			walker.version()
+		)?;
+
+		if self
+			.max_depth
+			.is_some_and(|max_depth| self.level > max_depth)
+		{
+			return write!(self.formatter, " {{ ... }}");
+		}
+
+		self.visited.insert(index);
+
+		let stored_fields = walker
+			.current()
+			.iter_fields()
+			.filter_map(|(field, value)| value.map(|value| (field.name(), value)))
+			.collect::<Vec<_>>();
+
+		if stored_fields.is_empty() {
+			return write!(self.formatter, " {{}}");
+		}
+
+		writeln!(self.formatter, " {{")?;
+		self.level += 1;
+		for (name, value) in stored_fields {
+			self.write_indent(depth + 1)?;
+			write!(self.formatter, "{name}: ")?;
+			let enum_name = self.registry.and_then(|registry| {
+				let definition = registry.lookup(walker.definition(), name)?;
+				definition
+					.raw_value(value)
+					.map(|raw| definition.format(raw))
+			});
+			match enum_name {
+				Some(enum_name) => write!(self.formatter, "{enum_name}")?,
+				None => self.write_value(walker, value, depth + 1)?,
+			}
+			writeln!(self.formatter)?;
+		}
+		self.level -= 1;
+		self.write_indent(depth)?;
+		write!(self.formatter, "}}")
+	}
+
+	fn write_value(&mut self, walker: &NodeWalker, value: &Value, depth: usize) -> fmt::Result {
+		match value {
+			Value::U8(value) => write!(self.formatter, "{value}"),
+			Value::I32(value) => write!(self.formatter, "{value}"),
+			Value::F32(value) => write!(self.formatter, "{value:?}"),
+			Value::String(value) => write!(self.formatter, "{value:?}"),
+
+			Value::Node(..) => match walker.resolve(value) {
+				Ok(Some(node)) => self.write_node(&node, depth),
+				Ok(None) => write!(self.formatter, "null"),
+				Err(_) => write!(self.formatter, "<invalid {value:?}>"),
+			},
+
+			Value::Vector(values) if values.is_empty() => write!(self.formatter, "[]"),
+
+			// Vectors of scalars, such as float arrays, are rendered on a single line.
+			Value::Vector(values) if values.iter().all(is_scalar) => {
+				write!(self.formatter, "[")?;
+				for (index, value) in values.iter().enumerate() {
+					if index > 0 {
+						write!(self.formatter, ", ")?;
+					}
+					self.write_value(walker, value, depth)?;
+				}
+				write!(self.formatter, "]")
+			}
+
+			Value::Vector(values) => {
+				writeln!(self.formatter, "[")?;
+				for value in values {
+					self.write_indent(depth + 1)?;
+					self.write_value(walker, value, depth + 1)?;
+					writeln!(self.formatter)?;
+				}
+				self.write_indent(depth)?;
+				write!(self.formatter, "]")
+			}
+		}
+	}
+
+	fn write_indent(&mut self, depth: usize) -> fmt::Result {
+		for _ in 0..depth {
+			self.formatter.write_str(INDENT)?;
+		}
+		Ok(())
+	}
+}
+
+fn is_scalar(value: &Value) -> bool {
+	matches!(value, Value::U8(..) | Value::I32(..) | Value::F32(..))
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, walker},
+		value::{Value, NULL_NODE},
+	};
+
+	#[test]
+	fn tree() {
+		let leaf = definition("leaf", None, vec![("value", FieldKind::Float)]);
+		let root = definition(
+			"root",
+			None,
+			vec![
+				("name", FieldKind::String),
+				("omitted", FieldKind::Integer),
+				("transform", FieldKind::Array(FieldKind::Float.into(), 4)),
+				(
+					"children",
+					FieldKind::Vector(FieldKind::Reference("leaf".into()).into()),
+				),
+				("parent", FieldKind::Reference("root".into())),
+				("empty", FieldKind::Reference("leaf".into())),
+			],
+		);
+		let walker = walker(vec![
+			node(
+				&root,
+				vec![
+					Some(Value::String("hello".into())),
+					None,
+					Some(Value::Vector(vec![
+						Value::F32(0.),
+						Value::F32(1.5),
+						Value::F32(0.),
+						Value::F32(1.),
+					])),
+					Some(Value::Vector(vec![Value::Node(1), Value::Node(1)])),
+					Some(Value::Node(0)),
+					Some(Value::Node(NULL_NODE)),
+				],
+			),
+			node(&leaf, vec![Some(Value::F32(2.))]),
+		]);
+
+		assert_eq!(
+			walker.to_string(),
+			concat!(
+				"#0 root (v0) {\n",
+				"  name: \"hello\"\n",
+				"  transform: [0.0, 1.5, 0.0, 1.0]\n",
+				"  children: [\n",
+				"    #1 leaf (v0) {\n",
+				"      value: 2.0\n",
+				"    }\n",
+				"    #1\n",
+				"  ]\n",
+				"  parent: #0\n",
+				"  empty: null\n",
+				"}\n",
+			)
+		);
+
+		assert_eq!(
+			walker.try_node(1).unwrap().dump().depth(0).to_string(),
+			"#1 leaf (v0) {\n  value: 2.0\n}\n"
+		);
+	}
+
+	#[test]
+	fn depth() {
+		let base = definition(
+			"base",
+			None,
+			vec![("next", FieldKind::Reference("base".into()))],
+		);
+		let walker = walker(vec![
+			node(&base, vec![Some(Value::Node(1))]),
+			node(&base, vec![Some(Value::Node(2))]),
+			node(&base, vec![None]),
+		]);
+
+		assert_eq!(
+			walker.dump().depth(1).to_string(),
+			concat!(
+				"#0 base (v0) {\n",
+				"  next: #1 base (v0) {\n",
+				"    next: #2 base (v0) { ... }\n",
+				"  }\n",
+				"}\n",
+			)
+		);
+	}
+}
@@ -0,0 +1,515 @@
+//! Interpretation of Havok enums and flag sets.
+//!
+//! Havok stores enum and flag fields as plain integers. The [`Registry`] maps
+//! fields of known classes to an [`EnumDefinition`] describing their values,
+//! which is used to render names in place of numbers, i.e. in dumps. Rust types
+//! are additionally provided for commonly used enums and flags, which can be
+//! converted from the value of their field.
+
+use std::{
+	collections::HashMap,
+	fmt,
+	ops::{BitAnd, BitOr},
+	sync::OnceLock,
+};
+
+use crate::{
+	error::Result,
+	from_node::FromField,
+	node::Definition,
+	value::{TryFromValueError, Value},
+	walker::NodeWalker,
+};
+
+/// Kind of values described by an [`EnumDefinition`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnumKind {
+	/// Values are exactly one of the defined items.
+	Enum,
+	/// Values are a combination of the bits of any number of defined items.
+	Flags,
+}
+
+/// Names of the values of a Havok enum or flag set.
+#[derive(Debug)]
+pub struct EnumDefinition {
+	name: &'static str,
+	kind: EnumKind,
+	items: &'static [(&'static str, i64)],
+}
+
+impl EnumDefinition {
+	/// Create a definition from its named items.
+	pub const fn new(
+		name: &'static str,
+		kind: EnumKind,
+		items: &'static [(&'static str, i64)],
+	) -> Self {
+		Self { name, kind, items }
+	}
+
+	/// Get the name of the enum, i.e. `hkaAnimation::AnimationType`.
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+
+	/// Get the kind of values described by this definition.
+	pub fn kind(&self) -> EnumKind {
+		self.kind
+	}
+
+	/// Get the named items of the enum, with their values.
+	pub fn items(&self) -> &'static [(&'static str, i64)] {
+		self.items
+	}
+
+	/// Get the name of the item with the specified value, if any.
+	pub fn item_name(&self, value: i64) -> Option<&'static str> {
+		self.items
+			.iter()
+			.find(|(_, item)| *item == value)
+			.map(|(name, _)| *name)
+	}
+
+	/// Get the raw integer of a stored value. Enums with negative items that
+	/// are stored as bytes are sign extended.
+	pub fn raw_value(&self, value: &Value) -> Option<i64> {
+		match value {
+			Value::I32(value) => Some((*value).into()),
+			Value::U8(value) if self.items.iter().any(|(_, item)| *item < 0) => {
+				Some((*value as i8).into())
+			}
+			Value::U8(value) => Some((*value).into()),
+			_ => None,
+		}
+	}
+
+	/// Format a raw value using the names of this definition. Values that do not
+	/// match any item, or bits that are not covered by any flag, are rendered as
+	/// `UNKNOWN(n)`.
+	pub fn format(&self, value: i64) -> String {
+		match self.kind {
+			EnumKind::Enum => match self.item_name(value) {
+				Some(name) => name.to_string(),
+				None => format!("UNKNOWN({value})"),
+			},
+
+			EnumKind::Flags => {
+				if value == 0 {
+					return self.item_name(0).unwrap_or("0").to_string();
+				}
+
+				let mut names = Vec::new();
+				let mut remaining = value;
+				for (name, item) in self.items {
+					if *item != 0 && value & item == *item {
+						names.push(name.to_string());
+						remaining &= !item;
+					}
+				}
+				if remaining != 0 {
+					names.push(format!("UNKNOWN({remaining:#x})"));
+				}
+				names.join(" | ")
+			}
+		}
+	}
+}
+
+/// Mapping of class fields to the enum definitions describing their values.
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+	classes: HashMap<String, HashMap<String, &'static EnumDefinition>>,
+}
+
+impl Registry {
+	/// Create an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Get the registry of enums for common Havok classes provided by this crate.
+	pub fn havok() -> &'static Registry {
+		static REGISTRY: OnceLock<Registry> = OnceLock::new();
+		REGISTRY.get_or_init(|| {
+			let mut registry = Registry::new();
+			registry.register("hkaAnimation", "type", &AnimationType::DEFINITION);
+			registry.register("hkaAnimationBinding", "blendHint", &BlendHint::DEFINITION);
+			registry.register("hkcdShape", "type", &ShapeType::DEFINITION);
+			registry.register("hkpMotion", "type", &MotionType::DEFINITION);
+			registry.register(
+				"hkpTypedBroadPhaseHandle",
+				"objectQualityType",
+				&CollidableQualityType::DEFINITION,
+			);
+			registry.register("hkbClipGenerator", "mode", &ClipPlaybackMode::DEFINITION);
+			registry.register("hkbClipGenerator", "flags", &ClipFlags::DEFINITION);
+			registry
+		})
+	}
+
+	/// Register the definition describing the values of a field. The field is
+	/// matched for the named class, as well as any class inheriting from it.
+	pub fn register(&mut self, class: &str, field: &str, definition: &'static EnumDefinition) {
+		self.classes
+			.entry(class.to_string())
+			.or_default()
+			.insert(field.to_string(), definition);
+	}
+
+	/// Look up the enum definition for a field of the specified struct,
+	/// including fields inherited from parent structs.
+	pub fn lookup(&self, definition: &Definition, field: &str) -> Option<&'static EnumDefinition> {
+		let mut current = Some(definition);
+		while let Some(definition) = current {
+			let found = self
+				.classes
+				.get(definition.name())
+				.and_then(|fields| fields.get(field));
+			if let Some(found) = found {
+				return Some(found);
+			}
+			current = definition.parent().map(|parent| parent.as_ref());
+		}
+		None
+	}
+
+	/// Format the value of a field of the node using its enum definition. Returns
+	/// `None` if the field has no registered definition, or is not an integer.
+	pub fn format(&self, node: &NodeWalker, field: &str) -> Option<String> {
+		let definition = self.lookup(node.definition(), field)?;
+		let value = node.field_or_default(field)?;
+		let raw = definition.raw_value(&value)?;
+		Some(definition.format(raw))
+	}
+}
+
+macro_rules! havok_enum {
+	(
+		$(#[$meta:meta])*
+		pub enum $type:ident = $name:literal {
+			$($variant:ident = $value:literal => $item:literal,)+
+		}
+	) => {
+		$(#[$meta])*
+		#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+		pub enum $type {
+			$(
+				#[doc = concat!("`", $item, "`.")]
+				$variant,
+			)+
+			/// Value that is not known to this crate.
+			Unknown(i32),
+		}
+
+		impl $type {
+			/// Definition of the Havok enum.
+			pub const DEFINITION: EnumDefinition =
+				EnumDefinition::new($name, EnumKind::Enum, &[$(($item, $value)),+]);
+		}
+
+		impl From<i32> for $type {
+			fn from(value: i32) -> Self {
+				match value {
+					$($value => Self::$variant,)+
+					other => Self::Unknown(other),
+				}
+			}
+		}
+
+		impl From<$type> for i32 {
+			fn from(value: $type) -> Self {
+				match value {
+					$($type::$variant => $value,)+
+					$type::Unknown(other) => other,
+				}
+			}
+		}
+
+		impl TryFrom<&Value> for $type {
+			type Error = TryFromValueError;
+
+			fn try_from(value: &Value) -> Result<Self, Self::Error> {
+				raw_value(&Self::DEFINITION, value).map(Self::from)
+			}
+		}
+
+		impl FromField for $type {
+			fn from_field(_node: &NodeWalker, value: &Value) -> Result<Self> {
+				Ok(Self::try_from(value)?)
+			}
+		}
+
+		impl fmt::Display for $type {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str(&Self::DEFINITION.format(i32::from(*self).into()))
+			}
+		}
+	};
+}
+
+macro_rules! havok_flags {
+	(
+		$(#[$meta:meta])*
+		pub struct $type:ident = $name:literal {
+			$($item:ident = $value:literal,)+
+		}
+	) => {
+		$(#[$meta])*
+		#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+		pub struct $type(pub i32);
+
+		#[allow(missing_docs)]
+		impl $type {
+			$(pub const $item: Self = Self($value);)+
+		}
+
+		impl $type {
+			/// Definition of the Havok flag set.
+			pub const DEFINITION: EnumDefinition = EnumDefinition::new(
+				$name,
+				EnumKind::Flags,
+				&[$((stringify!($item), $value)),+],
+			);
+
+			/// Check if all bits of `other` are set.
+			pub fn contains(self, other: Self) -> bool {
+				self.0 & other.0 == other.0
+			}
+		}
+
+		impl BitOr for $type {
+			type Output = Self;
+
+			fn bitor(self, other: Self) -> Self {
+				Self(self.0 | other.0)
+			}
+		}
+
+		impl BitAnd for $type {
+			type Output = Self;
+
+			fn bitand(self, other: Self) -> Self {
+				Self(self.0 & other.0)
+			}
+		}
+
+		impl TryFrom<&Value> for $type {
+			type Error = TryFromValueError;
+
+			fn try_from(value: &Value) -> Result<Self, Self::Error> {
+				raw_value(&Self::DEFINITION, value).map(Self)
+			}
+		}
+
+		impl FromField for $type {
+			fn from_field(_node: &NodeWalker, value: &Value) -> Result<Self> {
+				Ok(Self::try_from(value)?)
+			}
+		}
+
+		impl fmt::Display for $type {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str(&Self::DEFINITION.format(self.0.into()))
+			}
+		}
+	};
+}
+
+fn raw_value(definition: &EnumDefinition, value: &Value) -> Result<i32, TryFromValueError> {
+	definition
+		.raw_value(value)
+		.and_then(|raw| i32::try_from(raw).ok())
+		.ok_or_else(|| TryFromValueError::new(value, definition.name()))
+}
+
+havok_enum! {
+	/// Storage format of an animation, from `hkaAnimation::type`.
+	///
+	/// Values follow the numbering of the Havok 2014 SDK. Older SDKs, which
+	/// still provided delta and wavelet compression, numbered the types
+	/// differently, so their values are not interpreted correctly.
+	pub enum AnimationType = "hkaAnimation::AnimationType" {
+		UnknownAnimation = 0 => "HK_UNKNOWN_ANIMATION",
+		Interleaved = 1 => "HK_INTERLEAVED_ANIMATION",
+		Mirrored = 2 => "HK_MIRRORED_ANIMATION",
+		SplineCompressed = 3 => "HK_SPLINE_COMPRESSED_ANIMATION",
+		QuantizedCompressed = 4 => "HK_QUANTIZED_COMPRESSED_ANIMATION",
+		PredictiveCompressed = 5 => "HK_PREDICTIVE_COMPRESSED_ANIMATION",
+		ReferencePose = 6 => "HK_REFERENCE_POSE_ANIMATION",
+	}
+}
+
+havok_enum! {
+	/// How a bound animation is blended, from `hkaAnimationBinding::blendHint`.
+	pub enum BlendHint = "hkaAnimationBinding::BlendHint" {
+		Normal = 0 => "NORMAL",
+		AdditiveDeprecated = 1 => "ADDITIVE_DEPRECATED",
+		Additive = 2 => "ADDITIVE",
+	}
+}
+
+havok_enum! {
+	/// Type of a collision shape, from `hkcdShape::type`.
+	pub enum ShapeType = "hkcdShapeType::ShapeTypeEnum" {
+		Sphere = 0 => "SPHERE",
+		Cylinder = 1 => "CYLINDER",
+		Triangle = 2 => "TRIANGLE",
+		Box = 3 => "BOX",
+		Capsule = 4 => "CAPSULE",
+		ConvexVertices = 5 => "CONVEX_VERTICES",
+		TriSampledHeightFieldCollection = 6 => "TRI_SAMPLED_HEIGHT_FIELD_COLLECTION",
+		TriSampledHeightFieldBvTree = 7 => "TRI_SAMPLED_HEIGHT_FIELD_BV_TREE",
+		List = 8 => "LIST",
+		Mopp = 9 => "MOPP",
+		ConvexTranslate = 10 => "CONVEX_TRANSLATE",
+		ConvexTransform = 11 => "CONVEX_TRANSFORM",
+		SampledHeightField = 12 => "SAMPLED_HEIGHT_FIELD",
+		ExtendedMesh = 13 => "EXTENDED_MESH",
+		Transform = 14 => "TRANSFORM",
+		CompressedMesh = 15 => "COMPRESSED_MESH",
+		StaticCompound = 16 => "STATIC_COMPOUND",
+		BvCompressedMesh = 17 => "BV_COMPRESSED_MESH",
+		Collection = 18 => "COLLECTION",
+		User0 = 19 => "USER0",
+		User1 = 20 => "USER1",
+		User2 = 21 => "USER2",
+		BvTree = 22 => "BV_TREE",
+		Convex = 23 => "CONVEX",
+		ConvexPiece = 24 => "CONVEX_PIECE",
+		MultiSphere = 25 => "MULTI_SPHERE",
+		ConvexList = 26 => "CONVEX_LIST",
+		TriangleCollection = 27 => "TRIANGLE_COLLECTION",
+		HeightField = 28 => "HEIGHT_FIELD",
+		SphereRep = 29 => "SPHERE_REP",
+		Bv = 30 => "BV",
+		Plane = 31 => "PLANE",
+		PhantomCallback = 32 => "PHANTOM_CALLBACK",
+		MultiRay = 33 => "MULTI_RAY",
+		Invalid = 34 => "INVALID",
+	}
+}
+
+havok_enum! {
+	/// How a rigid body is simulated, from `hkpMotion::type`.
+	pub enum MotionType = "hkpMotion::MotionType" {
+		Invalid = 0 => "MOTION_INVALID",
+		Dynamic = 1 => "MOTION_DYNAMIC",
+		SphereInertia = 2 => "MOTION_SPHERE_INERTIA",
+		BoxInertia = 3 => "MOTION_BOX_INERTIA",
+		Keyframed = 4 => "MOTION_KEYFRAMED",
+		Fixed = 5 => "MOTION_FIXED",
+		ThinBoxInertia = 6 => "MOTION_THIN_BOX_INERTIA",
+		Character = 7 => "MOTION_CHARACTER",
+	}
+}
+
+havok_enum! {
+	/// Collision quality of an object, from `hkpTypedBroadPhaseHandle::objectQualityType`.
+	pub enum CollidableQualityType = "hkpCollidableQualityType" {
+		Invalid = -1 => "HK_COLLIDABLE_QUALITY_INVALID",
+		Fixed = 0 => "HK_COLLIDABLE_QUALITY_FIXED",
+		Keyframed = 1 => "HK_COLLIDABLE_QUALITY_KEYFRAMED",
+		Debris = 2 => "HK_COLLIDABLE_QUALITY_DEBRIS",
+		DebrisSimpleToi = 3 => "HK_COLLIDABLE_QUALITY_DEBRIS_SIMPLE_TOI",
+		Moving = 4 => "HK_COLLIDABLE_QUALITY_MOVING",
+		Critical = 5 => "HK_COLLIDABLE_QUALITY_CRITICAL",
+		Bullet = 6 => "HK_COLLIDABLE_QUALITY_BULLET",
+		User = 7 => "HK_COLLIDABLE_QUALITY_USER",
+		Character = 8 => "HK_COLLIDABLE_QUALITY_CHARACTER",
+		KeyframedReporting = 9 => "HK_COLLIDABLE_QUALITY_KEYFRAMED_REPORTING",
+	}
+}
+
+havok_enum! {
+	/// How a clip is played, from `hkbClipGenerator::mode`.
+	pub enum ClipPlaybackMode = "hkbClipGenerator::PlaybackMode" {
+		SinglePlay = 0 => "MODE_SINGLE_PLAY",
+		Looping = 1 => "MODE_LOOPING",
+		UserControlled = 2 => "MODE_USER_CONTROLLED",
+		PingPong = 3 => "MODE_PING_PONG",
+	}
+}
+
+havok_flags! {
+	/// Behavior flags of a clip, from `hkbClipGenerator::flags`.
+	pub struct ClipFlags = "hkbClipGenerator::ClipFlags" {
+		FLAG_CONTINUE_MOTION_AT_END = 0x1,
+		FLAG_SYNC_HALF_CYCLE_IN_PING_PONG_MODE = 0x2,
+		FLAG_MIRROR = 0x4,
+		FLAG_FORCE_DENSE_POSE = 0x8,
+		FLAG_DONT_CONVERT_ANNOTATIONS_TO_TRIGGERS = 0x10,
+		FLAG_IGNORE_MOTION = 0x20,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, walker},
+		value::Value,
+	};
+
+	use super::{AnimationType, ClipFlags, CollidableQualityType, Registry};
+
+	#[test]
+	fn format() {
+		assert_eq!(
+			AnimationType::SplineCompressed.to_string(),
+			"HK_SPLINE_COMPRESSED_ANIMATION"
+		);
+		assert_eq!(AnimationType::from(9), AnimationType::Unknown(9));
+		assert_eq!(AnimationType::Unknown(9).to_string(), "UNKNOWN(9)");
+
+		let flags = ClipFlags::FLAG_MIRROR | ClipFlags(0x40);
+		assert!(flags.contains(ClipFlags::FLAG_MIRROR));
+		assert_eq!(flags.to_string(), "FLAG_MIRROR | UNKNOWN(0x40)");
+		assert_eq!(ClipFlags::default().to_string(), "0");
+
+		assert_eq!(
+			CollidableQualityType::try_from(&Value::U8(255)).unwrap(),
+			CollidableQualityType::Invalid
+		);
+	}
+
+	#[test]
+	fn registry() {
+		let animation = definition("hkaAnimation", None, vec![("type", FieldKind::Integer)]);
+		let spline = definition(
+			"hkaSplineCompressedAnimation",
+			Some(&animation),
+			vec![("numFrames", FieldKind::Integer)],
+		);
+		let walker = walker(vec![node(
+			&spline,
+			vec![Some(Value::I32(3)), Some(Value::I32(3))],
+		)]);
+
+		let registry = Registry::havok();
+		assert_eq!(
+			registry.format(&walker, "type").as_deref(),
+			Some("HK_SPLINE_COMPRESSED_ANIMATION")
+		);
+		assert_eq!(registry.format(&walker, "numFrames"), None);
+		assert_eq!(
+			walker.get::<AnimationType>("type").unwrap(),
+			AnimationType::SplineCompressed
+		);
+
+		assert_eq!(
+			walker.to_string(),
+			concat!(
+				"#0 hkaSplineCompressedAnimation (v0) {\n",
+				"  type: HK_SPLINE_COMPRESSED_ANIMATION\n",
+				"  numFrames: 3\n",
+				"}\n",
+			)
+		);
+		assert!(walker
+			.dump()
+			.registry(None)
+			.to_string()
+			.contains("type: 3\n"));
+	}
+}
@@ -2,6 +2,8 @@ use std::io;
 
 use thiserror::Error;
 
+use crate::value::TryFromValueError;
+
 /// An error that occured.
 #[derive(Error, Debug)]
 pub enum Error {
@@ -16,4 +18,10 @@ impl From<io::Error> for Error {
 	}
 }
 
+impl From<TryFromValueError> for Error {
+	fn from(error: TryFromValueError) -> Self {
+		Self::Invalid(error.to_string())
+	}
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,314 @@
+use std::{rc::Rc, sync::Arc};
+
+use crate::{
+	error::{Error, Result},
+	math::{Matrix3, Matrix4, QsTransform, Quaternion, Transform, Vector4},
+	value::Value,
+	walker::NodeWalker,
+};
+
+/// Conversion from the value of a field, with access to the node containing
+/// the field such that references can be resolved.
+///
+/// This is implemented for common value types, as well as any type deriving
+/// `FromNode`, and is used by the derive macro to convert each field.
+pub trait FromField: Sized {
+	/// Convert the value of a field of the provided node.
+	fn from_field(node: &NodeWalker, value: &Value) -> Result<Self>;
+}
+
+macro_rules! impl_from_field_via_try_from {
+	($($type:ty),+ $(,)?) => {$(
+		impl FromField for $type {
+			fn from_field(_node: &NodeWalker, value: &Value) -> Result<Self> {
+				Ok(<$type>::try_from(value)?)
+			}
+		}
+	)+};
+}
+
+impl_from_field_via_try_from!(
+	bool,
+	u8,
+	u16,
+	i16,
+	i32,
+	u32,
+	usize,
+	f32,
+	String,
+	Vector4,
+	Quaternion,
+	Matrix3,
+	Matrix4,
+	QsTransform,
+	Transform,
+);
+
+impl FromField for Value {
+	fn from_field(_node: &NodeWalker, value: &Value) -> Result<Self> {
+		Ok(value.clone())
+	}
+}
+
+/// References convert to a walker for the referenced node.
+impl FromField for NodeWalker {
+	fn from_field(node: &NodeWalker, value: &Value) -> Result<Self> {
+		node.resolve(value)?
+			.ok_or_else(|| Error::Invalid("Unexpected null reference.".into()))
+	}
+}
+
+/// Null references convert to `None`. Other values are always `Some`.
+impl<T: FromField> FromField for Option<T> {
+	fn from_field(node: &NodeWalker, value: &Value) -> Result<Self> {
+		match node.resolve(value) {
+			Ok(None) => Ok(None),
+			_ => T::from_field(node, value).map(Some),
+		}
+	}
+}
+
+impl<T: FromField> FromField for Vec<T> {
+	fn from_field(node: &NodeWalker, value: &Value) -> Result<Self> {
+		value
+			.as_vector()
+			.ok_or_else(|| Error::Invalid(format!("Expected Vector, got {value:?}.")))?
+			.iter()
+			.enumerate()
+			.map(|(index, value)| {
+				T::from_field(node, value)
+					.map_err(|error| Error::Invalid(format!("[{index}]: {error}")))
+			})
+			.collect()
+	}
+}
+
+impl<T: FromField, const N: usize> FromField for [T; N] {
+	fn from_field(node: &NodeWalker, value: &Value) -> Result<Self> {
+		let values = Vec::<T>::from_field(node, value)?;
+		let length = values.len();
+		values
+			.try_into()
+			.map_err(|_| Error::Invalid(format!("Expected {N} elements, got {length}.")))
+	}
+}
+
+macro_rules! impl_from_field_wrapper {
+	($($wrapper:ident),+) => {$(
+		impl<T: FromField> FromField for $wrapper<T> {
+			fn from_field(node: &NodeWalker, value: &Value) -> Result<Self> {
+				T::from_field(node, value).map($wrapper::new)
+			}
+		}
+	)+};
+}
+
+impl_from_field_wrapper!(Box, Rc, Arc);
+
+/// Support functions for code generated by the derive macro. Not public API.
+#[doc(hidden)]
+pub mod __private {
+	use std::cell::RefCell;
+
+	use crate::{
+		document::Document,
+		error::{Error, Result},
+		value::Value,
+		walker::NodeWalker,
+	};
+
+	use super::FromField;
+
+	thread_local! {
+		/// Nodes currently being converted from references on this thread,
+		/// identified by their document and index.
+		static ACTIVE: RefCell<Vec<(*const Document, usize)>> = const { RefCell::new(Vec::new()) };
+	}
+
+	/// Removes the innermost active node when its conversion completes.
+	struct ActiveGuard;
+
+	impl Drop for ActiveGuard {
+		fn drop(&mut self) {
+			ACTIVE.with_borrow_mut(|active| active.pop());
+		}
+	}
+
+	/// Convert the node referenced by a value. A reference back to a node that
+	/// is still being converted, i.e. a cycle in the graph, is an error rather
+	/// than unbounded recursion.
+	pub fn from_reference<T>(node: &NodeWalker, value: &Value) -> Result<T>
+	where
+		T: for<'a> TryFrom<&'a NodeWalker, Error = Error>,
+	{
+		let node = NodeWalker::from_field(node, value)?;
+		let key = (node.document() as *const Document, node.index());
+		let entered = ACTIVE.with_borrow_mut(|active| {
+			if active.contains(&key) {
+				return false;
+			}
+			active.push(key);
+			true
+		});
+		if !entered {
+			return Err(Error::Invalid(format!(
+				"Cyclic reference to node {} ({}).",
+				node.index(),
+				node.name()
+			)));
+		}
+
+		let _guard = ActiveGuard;
+		T::try_from(&node)
+	}
+
+	pub fn check_class(
+		node: &NodeWalker,
+		class: &str,
+		min_version: Option<i32>,
+		max_version: Option<i32>,
+	) -> Result<()> {
+		// Versions are checked against the requested class, which may be an
+		// ancestor of the node's own class.
+		let mut definition = Some(node.definition());
+		while let Some(current) = definition {
+			if current.name() == class {
+				let version = current.version();
+				if min_version.is_some_and(|min| version < min)
+					|| max_version.is_some_and(|max| version > max)
+				{
+					return Err(Error::Invalid(format!(
+						"Unsupported {class} version {version}."
+					)));
+				}
+				return Ok(());
+			}
+			definition = current.parent();
+		}
+
+		Err(Error::Invalid(format!(
+			"Expected {class}, got {}.",
+			node.name()
+		)))
+	}
+
+	pub fn field<T: FromField>(node: &NodeWalker, name: &str) -> Result<T> {
+		node.get(name)
+	}
+
+	pub fn flatten<T>(node: &NodeWalker) -> Result<T>
+	where
+		T: for<'a> TryFrom<&'a NodeWalker, Error = Error>,
+	{
+		T::try_from(node)
+	}
+
+	pub fn field_or_default<T: FromField + Default>(node: &NodeWalker, name: &str) -> Result<T> {
+		match node.field_or_default(name) {
+			Some(_) => field(node, name),
+			None => Ok(T::default()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use mayhem_derive::FromNode;
+
+	use crate::{
+		node::FieldKind,
+		testing::{self, node, walker},
+		value::{Value, NULL_NODE},
+		walker::NodeWalker,
+	};
+
+	#[derive(Debug, FromNode)]
+	#[mayhem(class = "hkaSkeleton", min_version = 0, rename_all = "camelCase")]
+	struct Skeleton {
+		name: String,
+		parent_indices: Vec<i32>,
+		#[mayhem(rename = "referencePose")]
+		pose: Vec<[f32; 4]>,
+		bones: Vec<Bone>,
+		#[mayhem(default)]
+		float_slots: Vec<String>,
+		parent: Option<Box<Skeleton>>,
+	}
+
+	#[derive(Debug, FromNode)]
+	#[mayhem(class = "hkaBone")]
+	struct Bone {
+		name: String,
+	}
+
+	fn skeleton(version: i32, parent: usize) -> NodeWalker {
+		let (skeleton, bone) = testing::skeleton(
+			version,
+			vec![
+				(
+					"referencePose",
+					FieldKind::Vector(FieldKind::Array(FieldKind::Float.into(), 4).into()),
+				),
+				("parent", FieldKind::Reference("hkaSkeleton".into())),
+			],
+		);
+
+		walker(vec![
+			node(
+				&skeleton,
+				vec![
+					Some(Value::String("root".into())),
+					None,
+					Some(Value::Vector(vec![Value::Node(1)])),
+					Some(Value::Vector(vec![Value::Vector(vec![
+						Value::F32(0.),
+						Value::F32(1.),
+						Value::F32(2.),
+						Value::F32(3.),
+					])])),
+					Some(Value::Node(parent)),
+				],
+			),
+			node(&bone, vec![Some(Value::String("j_kosi".into())), None]),
+		])
+	}
+
+	#[test]
+	fn derive() {
+		let skeleton = Skeleton::try_from(skeleton(5, NULL_NODE)).unwrap();
+		assert_eq!(skeleton.name, "root");
+		assert!(skeleton.parent_indices.is_empty());
+		assert_eq!(skeleton.pose, [[0., 1., 2., 3.]]);
+		assert_eq!(skeleton.bones[0].name, "j_kosi");
+		assert!(skeleton.float_slots.is_empty());
+		assert!(skeleton.parent.is_none());
+	}
+
+	#[test]
+	fn derive_errors() {
+		let error = Skeleton::try_from(skeleton(-1, NULL_NODE)).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Invalid: Unsupported hkaSkeleton version -1."
+		);
+
+		let walker = skeleton(5, NULL_NODE);
+		let error = Bone::try_from(&walker).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Invalid: Expected hkaBone, got hkaSkeleton."
+		);
+	}
+
+	#[test]
+	fn cycle() {
+		let error = Skeleton::try_from(skeleton(5, 0)).unwrap_err();
+		assert!(
+			error
+				.to_string()
+				.ends_with("Cyclic reference to node 0 (hkaSkeleton)."),
+			"{error}"
+		);
+	}
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use crate::{value::Value, walker::NodeWalker};
+
+// Markers written before each item, so that differing structures cannot produce
+// the same byte stream.
+const TAG_NODE: u8 = 1;
+const TAG_BACK_REFERENCE: u8 = 2;
+const TAG_NULL: u8 = 3;
+const TAG_FIELD: u8 = 4;
+const TAG_U8: u8 = 5;
+const TAG_I32: u8 = 6;
+const TAG_F32: u8 = 7;
+const TAG_STRING: u8 = 8;
+const TAG_VECTOR: u8 = 9;
+
+pub fn content_hash(walker: &NodeWalker) -> u64 {
+	let mut hasher = ContentHasher {
+		state: Fnv64::new(),
+		ids: HashMap::new(),
+	};
+	hasher.hash_node(walker);
+	hasher.state.finish()
+}
+
+struct ContentHasher {
+	state: Fnv64,
+	// Ids are assigned to nodes in the order they are first reached from the
+	// root, which is independent of the order nodes were stored in the file.
+	ids: HashMap<usize, u64>,
+}
+
+impl ContentHasher {
+	fn hash_node(&mut self, walker: &NodeWalker) {
+		if let Some(id) = self.ids.get(&walker.index()) {
+			self.state.write_u8(TAG_BACK_REFERENCE);
+			self.state.write_u64(*id);
+			return;
+		}
+
+		let id = u64::try_from(self.ids.len()).unwrap();
+		self.ids.insert(walker.index(), id);
+
+		self.state.write_u8(TAG_NODE);
+		self.state.write_str(walker.name());
+		self.state.write_i32(walker.version());
+
+		// Fields are hashed by their effective value, such that a field explicitly
+		// storing a default value hashes the same as one that was omitted.
+		for field in walker.definition().fields() {
+			if let Some(value) = walker.field_or_default(field.name()) {
+				self.state.write_u8(TAG_FIELD);
+				self.state.write_str(field.name());
+				self.hash_value(walker, &value);
+			}
+		}
+	}
+
+	fn hash_value(&mut self, walker: &NodeWalker, value: &Value) {
+		match value {
+			Value::U8(value) => {
+				self.state.write_u8(TAG_U8);
+				self.state.write_u8(*value);
+			}
+
+			Value::I32(value) => {
+				self.state.write_u8(TAG_I32);
+				self.state.write_i32(*value);
+			}
+
+			Value::F32(value) => {
+				// Normalise values that compare equal, but differ in representation.
+				let bits = match value {
+					value if value.is_nan() => f32::NAN.to_bits(),
+					value if *value == 0. => 0,
+					value => value.to_bits(),
+				};
+				self.state.write_u8(TAG_F32);
+				self.state.write(&bits.to_le_bytes());
+			}
+
+			Value::String(value) => {
+				self.state.write_u8(TAG_STRING);
+				self.state.write_str(value);
+			}
+
+			Value::Node(..) => match walker.resolve(value).ok().flatten() {
+				Some(node) => self.hash_node(&node),
+				None => self.state.write_u8(TAG_NULL),
+			},
+
+			Value::Vector(values) => {
+				self.state.write_u8(TAG_VECTOR);
+				self.state.write_u64(u64::try_from(values.len()).unwrap());
+				for value in values {
+					self.hash_value(walker, value);
+				}
+			}
+		}
+	}
+}
+
+// 64-bit FNV-1a. The standard library hashers are not guaranteed to be stable
+// across releases, which would invalidate any persisted hashes.
+struct Fnv64(u64);
+
+impl Fnv64 {
+	fn new() -> Self {
+		Self(0xcbf29ce484222325)
+	}
+
+	fn write(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.0 ^= u64::from(*byte);
+			self.0 = self.0.wrapping_mul(0x100000001b3);
+		}
+	}
+
+	fn write_u8(&mut self, value: u8) {
+		self.write(&[value]);
+	}
+
+	fn write_i32(&mut self, value: i32) {
+		self.write(&value.to_le_bytes());
+	}
+
+	fn write_u64(&mut self, value: u64) {
+		self.write(&value.to_le_bytes());
+	}
+
+	fn write_str(&mut self, value: &str) {
+		self.write_u64(u64::try_from(value.len()).unwrap());
+		self.write(value.as_bytes());
+	}
+
+	fn finish(&self) -> u64 {
+		self.0
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, walker},
+		value::Value,
+	};
+
+	#[test]
+	fn index_order_independent() {
+		let base = definition(
+			"base",
+			None,
+			vec![
+				("value", FieldKind::Integer),
+				("next", FieldKind::Reference("base".into())),
+			],
+		);
+
+		let first = walker(vec![
+			node(&base, vec![Some(Value::I32(1)), Some(Value::Node(1))]),
+			node(&base, vec![Some(Value::I32(2)), Some(Value::Node(0))]),
+		]);
+		// Same graph, with an unrelated node and differing storage order.
+		let second = walker(vec![
+			node(&base, vec![Some(Value::I32(1)), Some(Value::Node(2))]),
+			node(&base, vec![Some(Value::I32(3)), None]),
+			node(&base, vec![Some(Value::I32(2)), Some(Value::Node(0))]),
+		]);
+		// Same values, different structure.
+		let third = walker(vec![
+			node(&base, vec![Some(Value::I32(1)), Some(Value::Node(1))]),
+			node(&base, vec![Some(Value::I32(2)), Some(Value::Node(1))]),
+		]);
+
+		assert_eq!(first.content_hash(), second.content_hash());
+		assert_ne!(first.content_hash(), third.content_hash());
+	}
+
+	#[test]
+	fn omitted_defaults() {
+		let base = definition("base", None, vec![("value", FieldKind::Float)]);
+		let stored = walker(vec![node(&base, vec![Some(Value::F32(-0.))])]);
+		let omitted = walker(vec![node(&base, vec![None])]);
+		assert_eq!(stored.content_hash(), omitted.content_hash());
+	}
+}
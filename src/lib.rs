@@ -3,11 +3,53 @@
 #![allow(clippy::module_inception)]
 #![warn(missing_debug_implementations, missing_docs)]
 
+#[cfg(feature = "serde")]
+mod de;
+mod diff;
+mod document;
+mod dump;
 mod error;
+mod from_node;
+mod hash;
 mod node;
+mod owned;
+#[cfg(feature = "serde")]
+mod ser;
 mod value;
+mod visitor;
 mod walker;
 
+#[cfg(test)]
+mod testing;
+
+pub mod animation;
+pub mod codegen;
+pub mod enums;
+pub mod math;
+pub mod patch;
+pub mod schema;
 pub mod tagfile;
 
-pub use {error::Error, value::Value, walker::NodeWalker};
+#[doc(hidden)]
+pub use from_node::__private;
+
+/// Derive a typed view over a node. See the `mayhem-derive` crate for details.
+#[cfg(any(feature = "derive", test))]
+pub use mayhem_derive::FromNode;
+
+// Allow derive macro output to refer to this crate by name within tests.
+#[cfg(test)]
+extern crate self as mayhem;
+
+pub use {
+	diff::{DiffOptions, Difference, DifferenceKind},
+	document::Document,
+	dump::Dump,
+	error::Error,
+	from_node::FromField,
+	node::{Definition, Field, FieldKind},
+	owned::{OwnedNode, OwnedTree},
+	value::{TryFromValueError, Value},
+	visitor::{Path, PathSegment, Visitor},
+	walker::{FieldLookup, NodeWalker},
+};
@@ -0,0 +1,72 @@
+use glam::{Mat3, Mat4, Quat, Vec3, Vec4};
+
+use super::{Matrix3, Matrix4, QsTransform, Quaternion, Transform, Vector4};
+
+impl From<Vector4> for Vec4 {
+	fn from(vector: Vector4) -> Self {
+		Vec4::from_array(vector.to_array())
+	}
+}
+
+impl From<Vec4> for Vector4 {
+	fn from(vector: Vec4) -> Self {
+		vector.to_array().into()
+	}
+}
+
+impl From<Quaternion> for Quat {
+	fn from(quaternion: Quaternion) -> Self {
+		Quat::from_array(quaternion.to_array())
+	}
+}
+
+impl From<Quat> for Quaternion {
+	fn from(quaternion: Quat) -> Self {
+		quaternion.to_array().into()
+	}
+}
+
+impl From<Matrix3> for Mat3 {
+	fn from(matrix: Matrix3) -> Self {
+		let [x, y, z] = matrix
+			.columns
+			.map(|column| Vec3::new(column.x, column.y, column.z));
+		Mat3::from_cols(x, y, z)
+	}
+}
+
+impl From<Mat3> for Matrix3 {
+	fn from(matrix: Mat3) -> Self {
+		Self::from_columns(
+			[matrix.x_axis, matrix.y_axis, matrix.z_axis]
+				.map(|column| Vector4::new(column.x, column.y, column.z, 0.)),
+		)
+	}
+}
+
+impl From<Matrix4> for Mat4 {
+	fn from(matrix: Matrix4) -> Self {
+		let [x, y, z, w] = matrix.columns.map(Vec4::from);
+		Mat4::from_cols(x, y, z, w)
+	}
+}
+
+impl From<Mat4> for Matrix4 {
+	fn from(matrix: Mat4) -> Self {
+		Self::from_columns(
+			[matrix.x_axis, matrix.y_axis, matrix.z_axis, matrix.w_axis].map(Into::into),
+		)
+	}
+}
+
+impl From<QsTransform> for Mat4 {
+	fn from(transform: QsTransform) -> Self {
+		transform.to_matrix().into()
+	}
+}
+
+impl From<Transform> for Mat4 {
+	fn from(transform: Transform) -> Self {
+		transform.to_matrix().into()
+	}
+}
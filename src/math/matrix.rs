@@ -0,0 +1,283 @@
+use std::ops::Mul;
+
+use super::{Quaternion, Vector4};
+
+/// 3x3 matrix, as stored by `hkMatrix3` and `hkRotation`.
+///
+/// Matrices are stored column-major, with each column padded to a [`Vector4`].
+/// The `w` component of each column is unused.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix3 {
+	/// Columns of the matrix.
+	pub columns: [Vector4; 3],
+}
+
+/// Rotation matrix, as stored by `hkRotation`.
+pub type Rotation = Matrix3;
+
+impl Default for Matrix3 {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl Matrix3 {
+	/// Identity matrix.
+	pub const IDENTITY: Self = Self::from_columns([
+		Vector4::new(1., 0., 0., 0.),
+		Vector4::new(0., 1., 0., 0.),
+		Vector4::new(0., 0., 1., 0.),
+	]);
+
+	/// Create a matrix from its columns.
+	pub const fn from_columns(columns: [Vector4; 3]) -> Self {
+		Self { columns }
+	}
+
+	/// Create a rotation matrix from a quaternion of unit length.
+	pub fn from_quaternion(quaternion: Quaternion) -> Self {
+		let Quaternion { x, y, z, w } = quaternion;
+		Self::from_columns([
+			Vector4::new(
+				1. - 2. * (y * y + z * z),
+				2. * (x * y + w * z),
+				2. * (x * z - w * y),
+				0.,
+			),
+			Vector4::new(
+				2. * (x * y - w * z),
+				1. - 2. * (x * x + z * z),
+				2. * (y * z + w * x),
+				0.,
+			),
+			Vector4::new(
+				2. * (x * z + w * y),
+				2. * (y * z - w * x),
+				1. - 2. * (x * x + y * y),
+				0.,
+			),
+		])
+	}
+
+	/// Get the values of the matrix, column by column, including padding.
+	pub fn to_array(self) -> [f32; 12] {
+		let mut array = [0.; 12];
+		for (chunk, column) in array.chunks_exact_mut(4).zip(self.columns) {
+			chunk.copy_from_slice(&column.to_array());
+		}
+		array
+	}
+
+	/// Get the transpose of the matrix.
+	pub fn transpose(self) -> Self {
+		let [x, y, z] = self.columns;
+		Self::from_columns([
+			Vector4::new(x.x, y.x, z.x, 0.),
+			Vector4::new(x.y, y.y, z.y, 0.),
+			Vector4::new(x.z, y.z, z.z, 0.),
+		])
+	}
+
+	/// Get the determinant of the matrix.
+	pub fn determinant(self) -> f32 {
+		let [x, y, z] = self.columns;
+		x.dot3(y.cross3(z))
+	}
+
+	/// Get the inverse of the matrix, if it is invertible.
+	pub fn inverse(self) -> Option<Self> {
+		let determinant = self.determinant();
+		if determinant == 0. {
+			return None;
+		}
+
+		let [x, y, z] = self.columns;
+		let scale = determinant.recip();
+		let adjugate = Self::from_columns([y.cross3(z), z.cross3(x), x.cross3(y)]).transpose();
+		Some(Self::from_columns(
+			adjugate.columns.map(|column| column * scale),
+		))
+	}
+
+	/// Transform the `x`, `y`, and `z` components of a vector. The `w` component
+	/// is left unchanged.
+	pub fn transform(self, vector: Vector4) -> Vector4 {
+		let [x, y, z] = self.columns;
+		let result = x * vector.x + y * vector.y + z * vector.z;
+		Vector4::new(result.x, result.y, result.z, vector.w)
+	}
+}
+
+impl From<[f32; 12]> for Matrix3 {
+	fn from(values: [f32; 12]) -> Self {
+		Self::from_columns(super::columns(&values))
+	}
+}
+
+impl Mul for Matrix3 {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		Self::from_columns(other.columns.map(|column| {
+			let result = self.transform(column);
+			Vector4::new(result.x, result.y, result.z, 0.)
+		}))
+	}
+}
+
+impl Mul<Vector4> for Matrix3 {
+	type Output = Vector4;
+
+	fn mul(self, vector: Vector4) -> Vector4 {
+		self.transform(vector)
+	}
+}
+
+/// 4x4 matrix, as stored by `hkMatrix4`. Matrices are stored column-major.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix4 {
+	/// Columns of the matrix.
+	pub columns: [Vector4; 4],
+}
+
+impl Default for Matrix4 {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl Matrix4 {
+	/// Identity matrix.
+	pub const IDENTITY: Self = Self::from_columns([
+		Vector4::new(1., 0., 0., 0.),
+		Vector4::new(0., 1., 0., 0.),
+		Vector4::new(0., 0., 1., 0.),
+		Vector4::new(0., 0., 0., 1.),
+	]);
+
+	/// Create a matrix from its columns.
+	pub const fn from_columns(columns: [Vector4; 4]) -> Self {
+		Self { columns }
+	}
+
+	/// Create an affine matrix from a 3x3 matrix and a translation.
+	pub fn from_matrix3_translation(matrix: Matrix3, translation: Vector4) -> Self {
+		let [x, y, z] = matrix.columns;
+		Self::from_columns([
+			Vector4::new(x.x, x.y, x.z, 0.),
+			Vector4::new(y.x, y.y, y.z, 0.),
+			Vector4::new(z.x, z.y, z.z, 0.),
+			Vector4::new(translation.x, translation.y, translation.z, 1.),
+		])
+	}
+
+	/// Get the values of the matrix, column by column.
+	pub fn to_array(self) -> [f32; 16] {
+		let mut array = [0.; 16];
+		for (chunk, column) in array.chunks_exact_mut(4).zip(self.columns) {
+			chunk.copy_from_slice(&column.to_array());
+		}
+		array
+	}
+
+	/// Get the transpose of the matrix.
+	pub fn transpose(self) -> Self {
+		let [x, y, z, w] = self.columns;
+		Self::from_columns([
+			Vector4::new(x.x, y.x, z.x, w.x),
+			Vector4::new(x.y, y.y, z.y, w.y),
+			Vector4::new(x.z, y.z, z.z, w.z),
+			Vector4::new(x.w, y.w, z.w, w.w),
+		])
+	}
+
+	/// Get the inverse of the matrix, if it is invertible.
+	pub fn inverse(self) -> Option<Self> {
+		// Gauss-Jordan elimination over the rows of [self | identity], in double
+		// precision to limit error accumulation.
+		let mut rows = [[0f64; 8]; 4];
+		for (index, row) in rows.iter_mut().enumerate() {
+			for (column, value) in self.columns.iter().enumerate() {
+				row[column] = f64::from(value.to_array()[index]);
+			}
+			row[4 + index] = 1.;
+		}
+
+		for pivot in 0..4 {
+			let best = (pivot..4)
+				.max_by(|&a, &b| rows[a][pivot].abs().total_cmp(&rows[b][pivot].abs()))
+				.unwrap_or(pivot);
+			if rows[best][pivot] == 0. {
+				return None;
+			}
+			rows.swap(pivot, best);
+
+			let scale = rows[pivot][pivot].recip();
+			rows[pivot].iter_mut().for_each(|value| *value *= scale);
+
+			let pivot_row = rows[pivot];
+			for (index, row) in rows.iter_mut().enumerate() {
+				if index == pivot {
+					continue;
+				}
+				let factor = row[pivot];
+				for (value, pivot_value) in row.iter_mut().zip(pivot_row) {
+					*value -= factor * pivot_value;
+				}
+			}
+		}
+
+		let column = |index: usize| {
+			Vector4::new(
+				rows[0][4 + index] as f32,
+				rows[1][4 + index] as f32,
+				rows[2][4 + index] as f32,
+				rows[3][4 + index] as f32,
+			)
+		};
+		Some(Self::from_columns([
+			column(0),
+			column(1),
+			column(2),
+			column(3),
+		]))
+	}
+
+	/// Transform a vector, including its `w` component.
+	pub fn transform(self, vector: Vector4) -> Vector4 {
+		let [x, y, z, w] = self.columns;
+		x * vector.x + y * vector.y + z * vector.z + w * vector.w
+	}
+
+	/// Transform a position, treating its `w` component as one.
+	pub fn transform_point(self, point: Vector4) -> Vector4 {
+		self.transform(Vector4::new(point.x, point.y, point.z, 1.))
+	}
+
+	/// Transform a direction, treating its `w` component as zero.
+	pub fn transform_vector(self, vector: Vector4) -> Vector4 {
+		self.transform(Vector4::new(vector.x, vector.y, vector.z, 0.))
+	}
+}
+
+impl From<[f32; 16]> for Matrix4 {
+	fn from(values: [f32; 16]) -> Self {
+		Self::from_columns(super::columns(&values))
+	}
+}
+
+impl Mul for Matrix4 {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		Self::from_columns(other.columns.map(|column| self.transform(column)))
+	}
+}
+
+impl Mul<Vector4> for Matrix4 {
+	type Output = Vector4;
+
+	fn mul(self, vector: Vector4) -> Vector4 {
+		self.transform(vector)
+	}
+}
@@ -0,0 +1,59 @@
+use super::{Matrix3, Matrix4, Quaternion, Vector4};
+
+impl From<Vector4> for mint::Vector4<f32> {
+	fn from(vector: Vector4) -> Self {
+		vector.to_array().into()
+	}
+}
+
+impl From<mint::Vector4<f32>> for Vector4 {
+	fn from(vector: mint::Vector4<f32>) -> Self {
+		Self::new(vector.x, vector.y, vector.z, vector.w)
+	}
+}
+
+impl From<Quaternion> for mint::Quaternion<f32> {
+	fn from(quaternion: Quaternion) -> Self {
+		Self {
+			v: [quaternion.x, quaternion.y, quaternion.z].into(),
+			s: quaternion.w,
+		}
+	}
+}
+
+impl From<mint::Quaternion<f32>> for Quaternion {
+	fn from(quaternion: mint::Quaternion<f32>) -> Self {
+		let mint::Quaternion { v, s } = quaternion;
+		Self::new(v.x, v.y, v.z, s)
+	}
+}
+
+impl From<Matrix3> for mint::ColumnMatrix3<f32> {
+	fn from(matrix: Matrix3) -> Self {
+		let [x, y, z] = matrix
+			.columns
+			.map(|column| [column.x, column.y, column.z].into());
+		Self { x, y, z }
+	}
+}
+
+impl From<mint::ColumnMatrix3<f32>> for Matrix3 {
+	fn from(matrix: mint::ColumnMatrix3<f32>) -> Self {
+		let mint::ColumnMatrix3 { x, y, z } = matrix;
+		Self::from_columns([x, y, z].map(|column| Vector4::new(column.x, column.y, column.z, 0.)))
+	}
+}
+
+impl From<Matrix4> for mint::ColumnMatrix4<f32> {
+	fn from(matrix: Matrix4) -> Self {
+		let [x, y, z, w] = matrix.columns.map(Into::into);
+		Self { x, y, z, w }
+	}
+}
+
+impl From<mint::ColumnMatrix4<f32>> for Matrix4 {
+	fn from(matrix: mint::ColumnMatrix4<f32>) -> Self {
+		let mint::ColumnMatrix4 { x, y, z, w } = matrix;
+		Self::from_columns([x, y, z, w].map(Into::into))
+	}
+}
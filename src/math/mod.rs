@@ -0,0 +1,225 @@
+//! Havok math types, as stored in fields of float arrays.
+//!
+//! Each type can be converted from the [`Value`] of its field, and implements
+//! the operations commonly required for animation work. Conversions to and from
+//! the types of other math libraries are available via the `glam`, `nalgebra`,
+//! and `mint` features.
+
+#[cfg(feature = "glam")]
+mod glam;
+mod matrix;
+#[cfg(feature = "mint")]
+mod mint;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
+mod quaternion;
+mod transform;
+mod vector;
+
+pub use {
+	matrix::{Matrix3, Matrix4, Rotation},
+	quaternion::Quaternion,
+	transform::{QsTransform, Transform},
+	vector::Vector4,
+};
+
+use crate::value::{TryFromValueError, Value};
+
+macro_rules! impl_try_from_value {
+	($($type:ty => $length:literal),+ $(,)?) => {$(
+		impl TryFrom<&Value> for $type {
+			type Error = TryFromValueError;
+
+			fn try_from(value: &Value) -> Result<Self, Self::Error> {
+				<[f32; $length]>::try_from(value).map(Self::from)
+			}
+		}
+	)+};
+}
+
+impl_try_from_value!(
+	Quaternion => 4,
+	Matrix3 => 12,
+	Matrix4 => 16,
+	QsTransform => 12,
+	Transform => 16,
+);
+
+impl TryFrom<&Value> for Vector4 {
+	type Error = TryFromValueError;
+
+	/// Tagfiles may store only the first three components, in which case `w` is
+	/// zero.
+	fn try_from(value: &Value) -> Result<Self, Self::Error> {
+		match value.as_vector().map(Vec::len) {
+			Some(3) => <[f32; 3]>::try_from(value).map(|[x, y, z]| Self::new(x, y, z, 0.)),
+			_ => <[f32; 4]>::try_from(value).map(Self::from),
+		}
+	}
+}
+
+/// Split a flat list of floats into consecutive vectors.
+fn columns<const N: usize>(values: &[f32]) -> [Vector4; N] {
+	std::array::from_fn(|index| {
+		let offset = index * 4;
+		Vector4::new(
+			values[offset],
+			values[offset + 1],
+			values[offset + 2],
+			values[offset + 3],
+		)
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use std::f32::consts::FRAC_PI_2;
+
+	use crate::{
+		testing::{assert_matrix_near, assert_near, EPSILON},
+		value::Value,
+	};
+
+	use super::{Matrix3, Matrix4, QsTransform, Quaternion, Transform, Vector4};
+
+	fn transform() -> QsTransform {
+		QsTransform::new(
+			Vector4::new(1., 2., 3., 0.),
+			Quaternion::from_axis_angle(Vector4::new(0., 0., 1., 0.), FRAC_PI_2),
+			Vector4::new(2., 2., 2., 1.),
+		)
+	}
+
+	#[test]
+	fn from_value() {
+		let value = Value::Vector((0..12).map(|index| Value::F32(index as f32)).collect());
+		let transform = QsTransform::try_from(&value).unwrap();
+		assert_eq!(transform.translation, Vector4::new(0., 1., 2., 3.));
+		assert_eq!(transform.rotation, Quaternion::new(4., 5., 6., 7.));
+		assert_eq!(transform.scale, Vector4::new(8., 9., 10., 11.));
+		assert_eq!(transform.to_array(), <[f32; 12]>::try_from(&value).unwrap());
+
+		assert!(Vector4::try_from(&value).is_err());
+
+		// Tagfiles may store only the first three components.
+		let value = Value::Vector([0., 0.6, 0.].into_iter().map(Value::F32).collect());
+		assert_eq!(
+			Vector4::try_from(&value).unwrap(),
+			Vector4::new(0., 0.6, 0., 0.)
+		);
+		assert!(Quaternion::try_from(&value).is_err());
+	}
+
+	#[test]
+	fn rotation() {
+		let rotation = transform().rotation;
+		let x = Vector4::new(1., 0., 0., 0.);
+		assert_near(rotation.rotate(x), Vector4::new(0., 1., 0., 0.), EPSILON);
+		assert_near(
+			Matrix3::from_quaternion(rotation) * x,
+			Vector4::new(0., 1., 0., 0.),
+			EPSILON,
+		);
+
+		let back = Quaternion::from_matrix(&Matrix3::from_quaternion(rotation));
+		assert!((back.dot(rotation).abs() - 1.).abs() < EPSILON);
+
+		let half = Quaternion::IDENTITY.slerp(rotation * rotation, 0.5);
+		assert!((half.dot(rotation).abs() - 1.).abs() < EPSILON);
+	}
+
+	#[test]
+	fn qs_transform() {
+		let transform = transform();
+		let point = Vector4::new(1., 0., 0., 1.);
+
+		let expected = Vector4::new(1., 4., 3., 1.);
+		assert_near(transform.transform_point(point), expected, EPSILON);
+		assert_near(
+			transform.to_matrix().transform_point(point),
+			expected,
+			EPSILON,
+		);
+
+		let combined = transform * transform;
+		assert_near(
+			combined.transform_point(point),
+			transform.transform_point(transform.transform_point(point)),
+			EPSILON,
+		);
+		assert_matrix_near(
+			combined.to_matrix(),
+			transform.to_matrix() * transform.to_matrix(),
+			EPSILON,
+		);
+
+		assert_near(
+			transform.inverse().transform_point(expected),
+			Vector4::new(1., 0., 0., 1.),
+			EPSILON,
+		);
+		assert_matrix_near(
+			transform.inverse().to_matrix(),
+			transform.to_matrix().inverse().unwrap(),
+			EPSILON,
+		);
+	}
+
+	#[test]
+	fn matrix() {
+		let matrix = transform().to_matrix();
+		assert_matrix_near(
+			matrix * matrix.inverse().unwrap(),
+			Matrix4::IDENTITY,
+			EPSILON,
+		);
+		assert_eq!(matrix.transpose().transpose(), matrix);
+		assert!(Matrix4::from([0.; 16]).inverse().is_none());
+
+		let rigid = Transform::from(transform());
+		assert_matrix_near(
+			rigid.inverse().to_matrix(),
+			rigid.to_matrix().inverse().unwrap(),
+			EPSILON,
+		);
+		assert_matrix_near(
+			(rigid * rigid.inverse()).to_matrix(),
+			Matrix4::IDENTITY,
+			EPSILON,
+		);
+
+		let rotation = rigid.rotation;
+		let inverse = rotation.inverse().unwrap();
+		assert_matrix_near(
+			Matrix4::from_matrix3_translation(inverse, Vector4::ZERO),
+			Matrix4::from_matrix3_translation(rotation.transpose(), Vector4::ZERO),
+			EPSILON,
+		);
+	}
+
+	#[cfg(feature = "glam")]
+	#[test]
+	fn glam() {
+		let transform = transform();
+		let matrix = glam::Mat4::from(transform);
+		assert_eq!(Matrix4::from(matrix), transform.to_matrix());
+		assert_near(
+			glam::Quat::from(transform.rotation)
+				.mul_vec3(glam::Vec3::X)
+				.extend(0.)
+				.into(),
+			transform.rotation.rotate(Vector4::new(1., 0., 0., 0.)),
+			EPSILON,
+		);
+	}
+
+	#[cfg(feature = "nalgebra")]
+	#[test]
+	fn nalgebra() {
+		let transform = transform();
+		let matrix = nalgebra::Matrix4::from(transform);
+		assert_eq!(Matrix4::from(matrix), transform.to_matrix());
+		let rotation = Quaternion::from(nalgebra::UnitQuaternion::from(transform.rotation));
+		assert!((rotation.dot(transform.rotation) - 1.).abs() < EPSILON);
+	}
+}
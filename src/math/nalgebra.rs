@@ -0,0 +1,85 @@
+use nalgebra as na;
+
+use super::{Matrix3, Matrix4, QsTransform, Quaternion, Transform, Vector4};
+
+impl From<Vector4> for na::Vector4<f32> {
+	fn from(vector: Vector4) -> Self {
+		na::Vector4::new(vector.x, vector.y, vector.z, vector.w)
+	}
+}
+
+impl From<na::Vector4<f32>> for Vector4 {
+	fn from(vector: na::Vector4<f32>) -> Self {
+		Self::new(vector.x, vector.y, vector.z, vector.w)
+	}
+}
+
+impl From<Quaternion> for na::Quaternion<f32> {
+	fn from(quaternion: Quaternion) -> Self {
+		na::Quaternion::new(quaternion.w, quaternion.x, quaternion.y, quaternion.z)
+	}
+}
+
+impl From<na::Quaternion<f32>> for Quaternion {
+	fn from(quaternion: na::Quaternion<f32>) -> Self {
+		Self::new(quaternion.i, quaternion.j, quaternion.k, quaternion.w)
+	}
+}
+
+impl From<Quaternion> for na::UnitQuaternion<f32> {
+	fn from(quaternion: Quaternion) -> Self {
+		na::UnitQuaternion::new_normalize(quaternion.into())
+	}
+}
+
+impl From<na::UnitQuaternion<f32>> for Quaternion {
+	fn from(quaternion: na::UnitQuaternion<f32>) -> Self {
+		quaternion.into_inner().into()
+	}
+}
+
+impl From<Matrix3> for na::Matrix3<f32> {
+	fn from(matrix: Matrix3) -> Self {
+		na::Matrix3::from_columns(
+			&matrix
+				.columns
+				.map(|column| na::Vector3::new(column.x, column.y, column.z)),
+		)
+	}
+}
+
+impl From<na::Matrix3<f32>> for Matrix3 {
+	fn from(matrix: na::Matrix3<f32>) -> Self {
+		Self::from_columns(std::array::from_fn(|index| {
+			let column = matrix.column(index);
+			Vector4::new(column[0], column[1], column[2], 0.)
+		}))
+	}
+}
+
+impl From<Matrix4> for na::Matrix4<f32> {
+	fn from(matrix: Matrix4) -> Self {
+		na::Matrix4::from_columns(&matrix.columns.map(na::Vector4::from))
+	}
+}
+
+impl From<na::Matrix4<f32>> for Matrix4 {
+	fn from(matrix: na::Matrix4<f32>) -> Self {
+		Self::from_columns(std::array::from_fn(|index| {
+			let column = matrix.column(index);
+			Vector4::new(column[0], column[1], column[2], column[3])
+		}))
+	}
+}
+
+impl From<QsTransform> for na::Matrix4<f32> {
+	fn from(transform: QsTransform) -> Self {
+		transform.to_matrix().into()
+	}
+}
+
+impl From<Transform> for na::Matrix4<f32> {
+	fn from(transform: Transform) -> Self {
+		transform.to_matrix().into()
+	}
+}
@@ -0,0 +1,190 @@
+use std::ops::{Mul, Neg};
+
+use super::{Matrix3, Vector4};
+
+/// Rotation quaternion, as stored by `hkQuaternion`. The vector part is stored
+/// in `x`, `y`, and `z`, followed by the scalar part in `w`.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion {
+	pub x: f32,
+	pub y: f32,
+	pub z: f32,
+	pub w: f32,
+}
+
+impl Default for Quaternion {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl Quaternion {
+	/// Quaternion representing no rotation.
+	pub const IDENTITY: Self = Self::new(0., 0., 0., 1.);
+
+	/// Create a quaternion from its components.
+	pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+		Self { x, y, z, w }
+	}
+
+	/// Create a quaternion rotating by `angle` radians around the `x`, `y`, and
+	/// `z` components of `axis`, which must be of unit length.
+	pub fn from_axis_angle(axis: Vector4, angle: f32) -> Self {
+		let (sin, cos) = (angle * 0.5).sin_cos();
+		Self::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+	}
+
+	/// Create a quaternion from a pure rotation matrix.
+	pub fn from_matrix(matrix: &Matrix3) -> Self {
+		let [x, y, z] = matrix.columns;
+		let trace = x.x + y.y + z.z;
+
+		let quaternion = if trace > 0. {
+			let s = (trace + 1.).sqrt() * 2.;
+			Self::new((y.z - z.y) / s, (z.x - x.z) / s, (x.y - y.x) / s, s / 4.)
+		} else if x.x > y.y && x.x > z.z {
+			let s = (1. + x.x - y.y - z.z).sqrt() * 2.;
+			Self::new(s / 4., (y.x + x.y) / s, (z.x + x.z) / s, (y.z - z.y) / s)
+		} else if y.y > z.z {
+			let s = (1. + y.y - x.x - z.z).sqrt() * 2.;
+			Self::new((y.x + x.y) / s, s / 4., (z.y + y.z) / s, (z.x - x.z) / s)
+		} else {
+			let s = (1. + z.z - x.x - y.y).sqrt() * 2.;
+			Self::new((z.x + x.z) / s, (z.y + y.z) / s, s / 4., (x.y - y.x) / s)
+		};
+
+		quaternion.normalize()
+	}
+
+	/// Get the components of the quaternion as an array.
+	pub fn to_array(self) -> [f32; 4] {
+		[self.x, self.y, self.z, self.w]
+	}
+
+	/// Dot product of the two quaternions.
+	pub fn dot(self, other: Self) -> f32 {
+		self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+	}
+
+	/// Length of the quaternion. Quaternions representing rotations have a
+	/// length of one.
+	pub fn length(self) -> f32 {
+		self.dot(self).sqrt()
+	}
+
+	/// Scale the quaternion to unit length.
+	pub fn normalize(self) -> Self {
+		let scale = self.length().recip();
+		Self::new(
+			self.x * scale,
+			self.y * scale,
+			self.z * scale,
+			self.w * scale,
+		)
+	}
+
+	/// Get the conjugate of the quaternion, which is the inverse rotation for
+	/// quaternions of unit length.
+	pub fn conjugate(self) -> Self {
+		Self::new(-self.x, -self.y, -self.z, self.w)
+	}
+
+	/// Get the inverse of the quaternion.
+	pub fn inverse(self) -> Self {
+		let conjugate = self.conjugate();
+		let scale = self.dot(self).recip();
+		Self::new(
+			conjugate.x * scale,
+			conjugate.y * scale,
+			conjugate.z * scale,
+			conjugate.w * scale,
+		)
+	}
+
+	/// Rotate the `x`, `y`, and `z` components of a vector. The `w` component
+	/// is left unchanged.
+	pub fn rotate(self, vector: Vector4) -> Vector4 {
+		let axis = Vector4::new(self.x, self.y, self.z, 0.);
+		let t = axis.cross3(vector) * 2.;
+		let rotated = vector + t * self.w + axis.cross3(t);
+		Vector4::new(rotated.x, rotated.y, rotated.z, vector.w)
+	}
+
+	/// Normalized linear interpolation along the shortest path between two
+	/// rotations. Cheaper than [`slerp`](Self::slerp), at the cost of a
+	/// non-constant angular velocity.
+	pub fn nlerp(self, other: Self, t: f32) -> Self {
+		let other = self.shortest(other);
+		Self::new(
+			self.x + (other.x - self.x) * t,
+			self.y + (other.y - self.y) * t,
+			self.z + (other.z - self.z) * t,
+			self.w + (other.w - self.w) * t,
+		)
+		.normalize()
+	}
+
+	/// Spherical linear interpolation along the shortest path between two rotations.
+	pub fn slerp(self, other: Self, t: f32) -> Self {
+		let other = self.shortest(other);
+		let cos = self.dot(other).min(1.);
+
+		// Nearly identical rotations are numerically unstable to slerp.
+		if cos > 0.9995 {
+			return self.nlerp(other, t);
+		}
+
+		let angle = cos.acos();
+		let sin = angle.sin();
+		let left = ((1. - t) * angle).sin() / sin;
+		let right = (t * angle).sin() / sin;
+		Self::new(
+			self.x * left + other.x * right,
+			self.y * left + other.y * right,
+			self.z * left + other.z * right,
+			self.w * left + other.w * right,
+		)
+	}
+
+	fn shortest(self, other: Self) -> Self {
+		match self.dot(other) < 0. {
+			true => -other,
+			false => other,
+		}
+	}
+}
+
+impl From<[f32; 4]> for Quaternion {
+	fn from([x, y, z, w]: [f32; 4]) -> Self {
+		Self::new(x, y, z, w)
+	}
+}
+
+impl From<Quaternion> for [f32; 4] {
+	fn from(quaternion: Quaternion) -> Self {
+		quaternion.to_array()
+	}
+}
+
+/// Composition of rotations, such that `(a * b).rotate(v) == a.rotate(b.rotate(v))`.
+impl Mul for Quaternion {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		Self::new(
+			self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+			self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+			self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+			self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+		)
+	}
+}
+
+impl Neg for Quaternion {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self::new(-self.x, -self.y, -self.z, -self.w)
+	}
+}
@@ -0,0 +1,213 @@
+use std::ops::Mul;
+
+use super::{Matrix3, Matrix4, Quaternion, Vector4};
+
+/// Translation, rotation, and scale, as stored by `hkQsTransform`. This is the
+/// representation used by Havok for bone transforms.
+///
+/// Transforms are applied as scale, then rotation, then translation.
+/// Composition and inversion are exact when scale is uniform; non-uniform scale
+/// combined with rotation cannot be represented in this form, and is
+/// approximated in the same way as Havok.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QsTransform {
+	/// Translation, in the `x`, `y`, and `z` components.
+	pub translation: Vector4,
+	/// Rotation.
+	pub rotation: Quaternion,
+	/// Scale, in the `x`, `y`, and `z` components.
+	pub scale: Vector4,
+}
+
+impl Default for QsTransform {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl QsTransform {
+	/// Transform that leaves positions unchanged.
+	pub const IDENTITY: Self = Self::new(Vector4::ZERO, Quaternion::IDENTITY, Vector4::ONE);
+
+	/// Create a transform from its components.
+	pub const fn new(translation: Vector4, rotation: Quaternion, scale: Vector4) -> Self {
+		Self {
+			translation,
+			rotation,
+			scale,
+		}
+	}
+
+	/// Get the values of the transform, as stored by Havok.
+	pub fn to_array(self) -> [f32; 12] {
+		let mut array = [0.; 12];
+		array[0..4].copy_from_slice(&self.translation.to_array());
+		array[4..8].copy_from_slice(&self.rotation.to_array());
+		array[8..12].copy_from_slice(&self.scale.to_array());
+		array
+	}
+
+	/// Build the equivalent affine matrix.
+	pub fn to_matrix(self) -> Matrix4 {
+		let [x, y, z] = Matrix3::from_quaternion(self.rotation).columns;
+		Matrix4::from_matrix3_translation(
+			Matrix3::from_columns([x * self.scale.x, y * self.scale.y, z * self.scale.z]),
+			self.translation,
+		)
+	}
+
+	/// Get the inverse of the transform.
+	pub fn inverse(self) -> Self {
+		let rotation = self.rotation.conjugate();
+		let scale = Vector4::new(self.scale.x, self.scale.y, self.scale.z, 1.).recip();
+		let translation = -rotation.rotate(self.translation) * scale;
+		Self::new(
+			Vector4::new(translation.x, translation.y, translation.z, 0.),
+			rotation,
+			scale,
+		)
+	}
+
+	/// Transform a position.
+	pub fn transform_point(self, point: Vector4) -> Vector4 {
+		let scaled = Vector4::new(
+			point.x * self.scale.x,
+			point.y * self.scale.y,
+			point.z * self.scale.z,
+			point.w,
+		);
+		let rotated = self.rotation.rotate(scaled);
+		Vector4::new(
+			rotated.x + self.translation.x,
+			rotated.y + self.translation.y,
+			rotated.z + self.translation.z,
+			point.w,
+		)
+	}
+
+	/// Interpolate between two transforms, such as for blending animation frames.
+	/// Translation and scale are interpolated linearly, and rotation spherically.
+	pub fn interpolate(self, other: Self, t: f32) -> Self {
+		Self::new(
+			self.translation.lerp(other.translation, t),
+			self.rotation.slerp(other.rotation, t),
+			self.scale.lerp(other.scale, t),
+		)
+	}
+}
+
+impl From<[f32; 12]> for QsTransform {
+	fn from(values: [f32; 12]) -> Self {
+		let [translation, rotation, scale] = super::columns(&values);
+		Self::new(translation, rotation.to_array().into(), scale)
+	}
+}
+
+/// Composition of transforms, such that `(a * b).transform_point(p) ==
+/// a.transform_point(b.transform_point(p))` for uniformly scaled transforms.
+/// Used to apply a child bone's local transform to its parent's.
+impl Mul for QsTransform {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		let translation = self.transform_point(Vector4::new(
+			other.translation.x,
+			other.translation.y,
+			other.translation.z,
+			0.,
+		));
+		Self::new(
+			translation,
+			(self.rotation * other.rotation).normalize(),
+			self.scale * other.scale,
+		)
+	}
+}
+
+/// Rotation and translation, as stored by `hkTransform`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Transform {
+	/// Rotation.
+	pub rotation: Matrix3,
+	/// Translation, in the `x`, `y`, and `z` components.
+	pub translation: Vector4,
+}
+
+impl Transform {
+	/// Transform that leaves positions unchanged.
+	pub const IDENTITY: Self = Self::new(Matrix3::IDENTITY, Vector4::ZERO);
+
+	/// Create a transform from its components.
+	pub const fn new(rotation: Matrix3, translation: Vector4) -> Self {
+		Self {
+			rotation,
+			translation,
+		}
+	}
+
+	/// Get the values of the transform, as stored by Havok.
+	pub fn to_array(self) -> [f32; 16] {
+		let mut array = [0.; 16];
+		array[0..12].copy_from_slice(&self.rotation.to_array());
+		array[12..16].copy_from_slice(&self.translation.to_array());
+		array
+	}
+
+	/// Build the equivalent affine matrix.
+	pub fn to_matrix(self) -> Matrix4 {
+		Matrix4::from_matrix3_translation(self.rotation, self.translation)
+	}
+
+	/// Get the inverse of the transform. The rotation is assumed to be
+	/// orthonormal, as is the case for all Havok rotations.
+	pub fn inverse(self) -> Self {
+		let rotation = self.rotation.transpose();
+		let translation = -rotation.transform(self.translation);
+		Self::new(
+			rotation,
+			Vector4::new(translation.x, translation.y, translation.z, 0.),
+		)
+	}
+
+	/// Transform a position.
+	pub fn transform_point(self, point: Vector4) -> Vector4 {
+		let rotated = self.rotation.transform(point);
+		Vector4::new(
+			rotated.x + self.translation.x,
+			rotated.y + self.translation.y,
+			rotated.z + self.translation.z,
+			point.w,
+		)
+	}
+}
+
+impl From<[f32; 16]> for Transform {
+	fn from(values: [f32; 16]) -> Self {
+		let [x, y, z, translation] = super::columns(&values);
+		Self::new(Matrix3::from_columns([x, y, z]), translation)
+	}
+}
+
+impl From<QsTransform> for Transform {
+	/// Convert a transform, discarding its scale.
+	fn from(transform: QsTransform) -> Self {
+		Self::new(
+			Matrix3::from_quaternion(transform.rotation),
+			transform.translation,
+		)
+	}
+}
+
+/// Composition of transforms, such that `(a * b).transform_point(p) ==
+/// a.transform_point(b.transform_point(p))`.
+impl Mul for Transform {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		let translation = self.transform_point(other.translation);
+		Self::new(
+			self.rotation * other.rotation,
+			Vector4::new(translation.x, translation.y, translation.z, 0.),
+		)
+	}
+}
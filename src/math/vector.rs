@@ -0,0 +1,169 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Four component vector, as stored by `hkVector4`.
+///
+/// Havok commonly stores three dimensional positions and directions in a
+/// four component vector. Methods suffixed with `3` ignore the `w` component.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector4 {
+	pub x: f32,
+	pub y: f32,
+	pub z: f32,
+	pub w: f32,
+}
+
+impl Vector4 {
+	/// Vector with all components set to zero.
+	pub const ZERO: Self = Self::splat(0.);
+
+	/// Vector with all components set to one.
+	pub const ONE: Self = Self::splat(1.);
+
+	/// Create a vector from its components.
+	pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+		Self { x, y, z, w }
+	}
+
+	/// Create a vector with all components set to the same value.
+	pub const fn splat(value: f32) -> Self {
+		Self::new(value, value, value, value)
+	}
+
+	/// Get the components of the vector as an array.
+	pub fn to_array(self) -> [f32; 4] {
+		[self.x, self.y, self.z, self.w]
+	}
+
+	/// Dot product of the `x`, `y`, and `z` components.
+	pub fn dot3(self, other: Self) -> f32 {
+		self.x * other.x + self.y * other.y + self.z * other.z
+	}
+
+	/// Dot product of all components.
+	pub fn dot4(self, other: Self) -> f32 {
+		self.dot3(other) + self.w * other.w
+	}
+
+	/// Cross product of the `x`, `y`, and `z` components. The `w` component of
+	/// the result is zero.
+	pub fn cross3(self, other: Self) -> Self {
+		Self::new(
+			self.y * other.z - self.z * other.y,
+			self.z * other.x - self.x * other.z,
+			self.x * other.y - self.y * other.x,
+			0.,
+		)
+	}
+
+	/// Length of the `x`, `y`, and `z` components.
+	pub fn length3(self) -> f32 {
+		self.dot3(self).sqrt()
+	}
+
+	/// Length of all components.
+	pub fn length4(self) -> f32 {
+		self.dot4(self).sqrt()
+	}
+
+	/// Scale the `x`, `y`, and `z` components to unit length. The `w` component
+	/// is left unchanged.
+	pub fn normalize3(self) -> Self {
+		let scale = self.length3().recip();
+		Self::new(self.x * scale, self.y * scale, self.z * scale, self.w)
+	}
+
+	/// Linearly interpolate between two vectors.
+	pub fn lerp(self, other: Self, t: f32) -> Self {
+		self + (other - self) * t
+	}
+
+	/// Reciprocal of each component.
+	pub fn recip(self) -> Self {
+		Self::new(
+			self.x.recip(),
+			self.y.recip(),
+			self.z.recip(),
+			self.w.recip(),
+		)
+	}
+
+	pub(crate) fn map(self, mut function: impl FnMut(f32) -> f32) -> Self {
+		Self::new(
+			function(self.x),
+			function(self.y),
+			function(self.z),
+			function(self.w),
+		)
+	}
+
+	pub(crate) fn zip(self, other: Self, mut function: impl FnMut(f32, f32) -> f32) -> Self {
+		Self::new(
+			function(self.x, other.x),
+			function(self.y, other.y),
+			function(self.z, other.z),
+			function(self.w, other.w),
+		)
+	}
+}
+
+impl From<[f32; 4]> for Vector4 {
+	fn from([x, y, z, w]: [f32; 4]) -> Self {
+		Self::new(x, y, z, w)
+	}
+}
+
+impl From<Vector4> for [f32; 4] {
+	fn from(vector: Vector4) -> Self {
+		vector.to_array()
+	}
+}
+
+impl Add for Vector4 {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		self.zip(other, |left, right| left + right)
+	}
+}
+
+impl Sub for Vector4 {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self {
+		self.zip(other, |left, right| left - right)
+	}
+}
+
+/// Component-wise multiplication.
+impl Mul for Vector4 {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		self.zip(other, |left, right| left * right)
+	}
+}
+
+impl Mul<f32> for Vector4 {
+	type Output = Self;
+
+	fn mul(self, scale: f32) -> Self {
+		self.map(|value| value * scale)
+	}
+}
+
+impl Div<f32> for Vector4 {
+	type Output = Self;
+
+	fn div(self, scale: f32) -> Self {
+		self.map(|value| value / scale)
+	}
+}
+
+impl Neg for Vector4 {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		self.map(|value| -value)
+	}
+}
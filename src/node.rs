@@ -1,50 +1,266 @@
-use std::rc::Rc;
+use std::{collections::HashMap, fmt, sync::Arc};
 
-use crate::value::Value;
+use crate::value::{Value, NULL_NODE};
 
 #[derive(Debug)]
 pub struct Node {
-	pub definition: Rc<Definition>,
-	pub field_mask: Vec<bool>,
+	pub definition: Arc<Definition>,
 	pub values: Vec<Value>,
+	// Offset into `values` for each of the definition's fields, if stored.
+	offsets: Vec<Option<usize>>,
 }
 
+impl Node {
+	pub fn new(definition: Arc<Definition>, field_mask: &[bool], values: Vec<Value>) -> Self {
+		let offsets = field_mask
+			.iter()
+			.scan(0usize, |offset, stored| {
+				Some(stored.then(|| {
+					let current = *offset;
+					*offset += 1;
+					current
+				}))
+			})
+			.collect();
+
+		Self {
+			definition,
+			values,
+			offsets,
+		}
+	}
+
+	/// Get the value of the field in the specified slot, if stored.
+	pub fn value(&self, slot: usize) -> Option<&Value> {
+		self.offsets
+			.get(slot)
+			.copied()
+			.flatten()
+			.map(|offset| &self.values[offset])
+	}
+
+	/// Iterate over the node's fields, alongside their values if stored.
+	pub fn iter_fields(&self) -> impl Iterator<Item = (&Field, Option<&Value>)> {
+		self.definition
+			.fields()
+			.iter()
+			.zip(&self.offsets)
+			.map(|(field, offset)| (field, offset.map(|offset| &self.values[offset])))
+	}
+}
+
+/// Schema of a struct, describing the fields stored by nodes of that struct.
 #[derive(Debug)]
 pub struct Definition {
-	pub name: String,
-	pub version: i32,
-	// TODO: Not super happy with the Rc here, though it's relatively ergonomic...
-	pub parent: Option<Rc<Definition>>,
-	pub fields: Vec<Field>,
+	pub(crate) name: String,
+	pub(crate) version: i32,
+	// TODO: Not super happy with the Arc here, though it's relatively ergonomic...
+	pub(crate) parent: Option<Arc<Definition>>,
+
+	// Fields are stored flattened, with inherited fields first. Lookups by name
+	// are resolved against the flattened slots.
+	fields: Vec<Field>,
+	own_fields_start: usize,
+	slots: HashMap<String, usize>,
 }
 
 impl Definition {
-	// TODO: this generates a bunch of intermediate Vecs, which would be good to avoid.
-	pub fn fields(&self) -> Vec<&Field> {
-		self.parent
-			.iter()
-			.flat_map(|definition| definition.fields())
-			.chain(self.fields.iter())
-			.collect()
+	pub(crate) fn new(
+		name: String,
+		version: i32,
+		parent: Option<Arc<Definition>>,
+		own_fields: Vec<Field>,
+	) -> Self {
+		let mut fields = parent
+			.as_ref()
+			.map(|parent| parent.fields.clone())
+			.unwrap_or_default();
+		let own_fields_start = fields.len();
+		fields.extend(own_fields);
+
+		// If a field name is repeated, the first (i.e. most-inherited) field wins.
+		let mut slots = HashMap::with_capacity(fields.len());
+		for (slot, field) in fields.iter().enumerate() {
+			slots.entry(field.name.clone()).or_insert(slot);
+		}
+
+		Self {
+			name,
+			version,
+			parent,
+			fields,
+			own_fields_start,
+			slots,
+		}
+	}
+
+	/// Get the struct's name.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the struct's version.
+	pub fn version(&self) -> i32 {
+		self.version
+	}
+
+	/// Get the definition of the struct this struct inherits from, if any.
+	pub fn parent(&self) -> Option<&Arc<Definition>> {
+		self.parent.as_ref()
+	}
+
+	/// Get the fields declared by this struct, excluding inherited fields.
+	pub fn own_fields(&self) -> &[Field] {
+		&self.fields[self.own_fields_start..]
+	}
+
+	/// Get all fields of this struct, including inherited fields, in storage order.
+	pub fn fields(&self) -> &[Field] {
+		&self.fields
+	}
+
+	/// Get the slot of the specified field within [`fields`](Self::fields).
+	pub fn slot(&self, name: &str) -> Option<usize> {
+		self.slots.get(name).copied()
+	}
+
+	/// Get the specified field, including inherited fields.
+	pub fn field(&self, name: &str) -> Option<&Field> {
+		self.slot(name).map(|slot| &self.fields[slot])
+	}
+
+	/// Check if this struct is, or inherits from, the specified struct.
+	pub fn is_a(&self, name: &str) -> bool {
+		self.name == name || self.parent.as_ref().is_some_and(|parent| parent.is_a(name))
 	}
 }
 
 // TODO: maybe move fields to seperate module?
-#[derive(Debug)]
+/// Schema of a single field within a struct.
+#[derive(Clone, Debug)]
 pub struct Field {
-	pub name: String,
-	pub kind: FieldKind,
+	pub(crate) name: String,
+	pub(crate) kind: FieldKind,
 }
 
-#[derive(Debug)]
+impl Field {
+	/// Get the field's name.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the kind of value stored in the field.
+	pub fn kind(&self) -> &FieldKind {
+		&self.kind
+	}
+}
+
+/// Kind of value stored in a field.
+#[derive(Clone, Debug)]
 pub enum FieldKind {
+	/// No value.
 	Void,
+	/// Single byte, stored as [`Value::U8`].
 	Byte,
+	/// Floating point number, stored as [`Value::F32`].
 	Float,
+	/// Integer, stored as [`Value::I32`].
 	Integer,
+	/// String, stored as [`Value::String`].
 	String,
+	/// Inline struct of the named definition, stored as a [`Value::Node`].
 	Struct(String),
+	/// Reference to a node of the named definition, stored as a [`Value::Node`].
 	Reference(String),
+	/// Variable length list of the inner kind, stored as a [`Value::Vector`].
 	Vector(Box<FieldKind>),
+	/// Fixed length list of the inner kind, stored as a [`Value::Vector`].
 	Array(Box<FieldKind>, usize),
 }
+
+impl FieldKind {
+	/// Get the name of the struct referenced by this kind, if it is a struct or
+	/// reference kind.
+	pub fn class_name(&self) -> Option<&str> {
+		match self {
+			Self::Struct(name) | Self::Reference(name) => Some(name),
+			_ => None,
+		}
+	}
+
+	/// Get the kind of the elements of this kind, if it is a vector or array kind.
+	pub fn inner(&self) -> Option<&FieldKind> {
+		match self {
+			Self::Vector(inner) | Self::Array(inner, _) => Some(inner),
+			_ => None,
+		}
+	}
+
+	/// Build the zero value for this kind, as used by Havok for fields that were
+	/// omitted from storage.
+	///
+	/// Structs, and arrays of structs, have no default. Their values are nodes
+	/// within a document, which cannot be created from the kind alone, so callers
+	/// must handle omitted struct fields themselves, i.e. by checking for
+	/// [`FieldLookup::NotStored`](crate::FieldLookup::NotStored).
+	pub fn default_value(&self) -> Option<Value> {
+		let value = match self {
+			Self::Void | Self::Struct(..) => return None,
+			Self::Byte => Value::U8(0),
+			Self::Integer => Value::I32(0),
+			Self::Float => Value::F32(0.),
+			Self::String => Value::String("".into()),
+			Self::Reference(..) => Value::Node(NULL_NODE),
+			Self::Vector(..) => Value::Vector(vec![]),
+			Self::Array(inner, count) => {
+				let value = inner.default_value()?;
+				Value::Vector(vec![value; *count])
+			}
+		};
+
+		Some(value)
+	}
+}
+
+impl fmt::Display for FieldKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Void => write!(f, "Void"),
+			Self::Byte => write!(f, "Byte"),
+			Self::Float => write!(f, "Float"),
+			Self::Integer => write!(f, "Integer"),
+			Self::String => write!(f, "String"),
+			Self::Struct(name) => write!(f, "Struct<{name}>"),
+			Self::Reference(name) => write!(f, "Reference<{name}>"),
+			Self::Vector(inner) => write!(f, "Vector<{inner}>"),
+			Self::Array(inner, count) => write!(f, "Array<{inner}, {count}>"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::testing::definition;
+
+	use super::{Field, FieldKind};
+
+	#[test]
+	fn inherited_fields() {
+		let base = definition("base", None, vec![("a", FieldKind::Integer)]);
+		let derived = definition("derived", Some(&base), vec![("b", FieldKind::Float)]);
+
+		fn names(fields: &[Field]) -> Vec<&str> {
+			fields.iter().map(|field| field.name()).collect()
+		}
+
+		assert_eq!(names(derived.fields()), ["a", "b"]);
+		assert_eq!(names(derived.own_fields()), ["b"]);
+
+		assert!(matches!(
+			derived.field("a").map(|field| field.kind()),
+			Some(FieldKind::Integer)
+		));
+		assert!(derived.field("c").is_none());
+		assert!(derived.is_a("base") && !base.is_a("derived"));
+	}
+}
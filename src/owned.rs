@@ -0,0 +1,165 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+	value::{Value, NULL_NODE},
+	walker::NodeWalker,
+};
+
+/// Self-contained copy of a node, and every node reachable from it.
+///
+/// Nodes within the tree are identified by an id local to the tree, with the
+/// root node always having id `0`. Reference values within the tree are stored
+/// as [`Value::Node`] containing the id of the referenced node, such that
+/// multiple references to a single node, as well as cycles, are preserved.
+#[derive(Clone, Debug)]
+pub struct OwnedTree {
+	nodes: Vec<OwnedNode>,
+}
+
+impl OwnedTree {
+	pub(crate) fn new(walker: &NodeWalker) -> Self {
+		let mut ids = HashMap::from([(walker.index(), 0usize)]);
+		let mut queue = VecDeque::from([walker.clone()]);
+		let mut nodes = Vec::new();
+
+		// Nodes are assigned ids in the order they are discovered, and visited in
+		// that same order, such that each node is pushed at the index of its id.
+		while let Some(current) = queue.pop_front() {
+			let mut remap = |index: usize| {
+				let next_id = ids.len();
+				*ids.entry(index).or_insert_with(|| {
+					queue.push_back(current.node(index).unwrap());
+					next_id
+				})
+			};
+
+			let fields = current
+				.current()
+				.iter_fields()
+				.filter_map(|(field, value)| {
+					value.map(|value| (field.name().to_string(), detach_value(value, &mut remap)))
+				})
+				.collect();
+
+			nodes.push(OwnedNode {
+				name: current.name().to_string(),
+				version: current.version(),
+				fields,
+			});
+		}
+
+		Self { nodes }
+	}
+
+	/// Get the root node of the tree.
+	pub fn root(&self) -> &OwnedNode {
+		&self.nodes[0]
+	}
+
+	/// Get the node with the specified id.
+	pub fn node(&self, id: usize) -> Option<&OwnedNode> {
+		self.nodes.get(id)
+	}
+
+	/// Get all nodes within the tree, indexed by id.
+	pub fn nodes(&self) -> &[OwnedNode] {
+		&self.nodes
+	}
+
+	/// Get the node referenced by a value, if it is a non-null reference.
+	pub fn resolve(&self, value: &Value) -> Option<&OwnedNode> {
+		value.as_node().and_then(|id| self.node(*id))
+	}
+}
+
+fn detach_value(value: &Value, remap: &mut impl FnMut(usize) -> usize) -> Value {
+	match value {
+		Value::Node(NULL_NODE) => Value::Node(NULL_NODE),
+		Value::Node(index) => Value::Node(remap(*index)),
+		Value::Vector(values) => Value::Vector(
+			values
+				.iter()
+				.map(|value| detach_value(value, remap))
+				.collect(),
+		),
+		other => other.clone(),
+	}
+}
+
+/// Detached copy of a single node within an [`OwnedTree`].
+#[derive(Clone, Debug)]
+pub struct OwnedNode {
+	name: String,
+	version: i32,
+	fields: Vec<(String, Value)>,
+}
+
+impl OwnedNode {
+	/// Get the node's struct name.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the node's struct version.
+	pub fn version(&self) -> i32 {
+		self.version
+	}
+
+	/// Iterate over the node's stored fields, in storage order.
+	pub fn fields(&self) -> impl Iterator<Item = (&str, &Value)> {
+		self.fields
+			.iter()
+			.map(|(name, value)| (name.as_str(), value))
+	}
+
+	/// Get the value of the specified field, if it was stored.
+	pub fn field(&self, name: &str) -> Option<&Value> {
+		self.fields
+			.iter()
+			.find(|(field, _value)| field == name)
+			.map(|(_field, value)| value)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, walker},
+		value::Value,
+	};
+
+	#[test]
+	fn detach() {
+		let base = definition(
+			"base",
+			None,
+			vec![
+				("value", FieldKind::Integer),
+				("next", FieldKind::Reference("base".into())),
+			],
+		);
+		// Node 1 is unreachable from node 2, and should be excluded.
+		let walker = walker(vec![
+			node(&base, vec![Some(Value::I32(0)), Some(Value::Node(3))]),
+			node(&base, vec![Some(Value::I32(1)), None]),
+			node(&base, vec![Some(Value::I32(2)), Some(Value::Node(0))]),
+			node(&base, vec![Some(Value::I32(3)), Some(Value::Node(2))]),
+		]);
+
+		let tree = walker.try_node(2).unwrap().detach();
+		let values = tree
+			.nodes()
+			.iter()
+			.map(|node| {
+				(
+					*node.field("value").unwrap().as_i32().unwrap(),
+					*node.field("next").unwrap().as_node().unwrap(),
+				)
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(values, [(2, 1), (0, 2), (3, 0)]);
+		assert_eq!(tree.root().name(), "base");
+	}
+}
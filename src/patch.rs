@@ -0,0 +1,453 @@
+//! Upgrading of nodes between class versions.
+//!
+//! A [`Patch`] describes the changes between two versions of a single class,
+//! in the same manner as the patches shipped with Havok. A [`Patcher`] applies
+//! registered patches to every node of a document, chaining them where
+//! required, such that typed code only has to support the newest layout of
+//! each class.
+
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+use crate::{
+	document::Document,
+	error::{Error, Result},
+	node::{Definition, Field, FieldKind, Node},
+	value::Value,
+	walker::NodeWalker,
+};
+
+/// Conversion of a stored value to a new field kind.
+pub type ConvertValue = fn(&Value) -> Result<Value>;
+
+#[derive(Clone, Debug)]
+enum Step {
+	Add {
+		field: Field,
+		default: Option<Value>,
+	},
+	Remove(String),
+	Rename {
+		from: String,
+		to: String,
+	},
+	ChangeKind {
+		name: String,
+		kind: FieldKind,
+		convert: ConvertValue,
+	},
+}
+
+/// Changes to the fields declared by a class between two of its versions.
+///
+/// Steps are applied in the order they are added. Only the class's own fields
+/// are affected - inherited fields are changed by patches to the parent class.
+#[derive(Clone, Debug)]
+pub struct Patch {
+	class: String,
+	from_version: i32,
+	to_version: i32,
+	steps: Vec<Step>,
+}
+
+impl Patch {
+	/// Create an empty patch upgrading the named class between two versions.
+	pub fn new(class: impl Into<String>, from_version: i32, to_version: i32) -> Self {
+		Self {
+			class: class.into(),
+			from_version,
+			to_version,
+			steps: Vec::new(),
+		}
+	}
+
+	/// Get the name of the class this patch applies to.
+	pub fn class(&self) -> &str {
+		&self.class
+	}
+
+	/// Get the version of the class this patch upgrades from.
+	pub fn from_version(&self) -> i32 {
+		self.from_version
+	}
+
+	/// Get the version of the class this patch upgrades to.
+	pub fn to_version(&self) -> i32 {
+		self.to_version
+	}
+
+	/// Add a new field after the existing fields. If a default is provided, it
+	/// is stored in every upgraded node, otherwise the field is left unstored.
+	pub fn add_field(
+		mut self,
+		name: impl Into<String>,
+		kind: FieldKind,
+		default: Option<Value>,
+	) -> Self {
+		let field = Field {
+			name: name.into(),
+			kind,
+		};
+		self.steps.push(Step::Add { field, default });
+		self
+	}
+
+	/// Remove a field, discarding any stored values.
+	pub fn remove_field(mut self, name: impl Into<String>) -> Self {
+		self.steps.push(Step::Remove(name.into()));
+		self
+	}
+
+	/// Rename a field, retaining its kind and values.
+	pub fn rename_field(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+		self.steps.push(Step::Rename {
+			from: from.into(),
+			to: to.into(),
+		});
+		self
+	}
+
+	/// Change the kind of a field, converting stored values with the provided function.
+	pub fn change_kind(
+		mut self,
+		name: impl Into<String>,
+		kind: FieldKind,
+		convert: ConvertValue,
+	) -> Self {
+		self.steps.push(Step::ChangeKind {
+			name: name.into(),
+			kind,
+			convert,
+		});
+		self
+	}
+
+	fn apply(&self, fields: &mut Vec<(Field, Option<Value>)>) -> Result<()> {
+		for step in &self.steps {
+			match step {
+				Step::Add { field, default } => {
+					if fields
+						.iter()
+						.any(|(existing, _)| existing.name == field.name)
+					{
+						return Err(self.error(&field.name, "already exists"));
+					}
+					fields.push((field.clone(), default.clone()));
+				}
+
+				Step::Remove(name) => {
+					let index = self.position(fields, name)?;
+					fields.remove(index);
+				}
+
+				Step::Rename { from, to } => {
+					let index = self.position(fields, from)?;
+					fields[index].0.name = to.clone();
+				}
+
+				Step::ChangeKind {
+					name,
+					kind,
+					convert,
+				} => {
+					let index = self.position(fields, name)?;
+					let (field, value) = &mut fields[index];
+					field.kind = kind.clone();
+					if let Some(value) = value {
+						*value =
+							convert(value).map_err(|error| self.error(name, &error.to_string()))?;
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn position(&self, fields: &[(Field, Option<Value>)], name: &str) -> Result<usize> {
+		fields
+			.iter()
+			.position(|(field, _)| field.name == name)
+			.ok_or_else(|| self.error(name, "does not exist"))
+	}
+
+	fn error(&self, field: &str, message: &str) -> Error {
+		Error::Invalid(format!(
+			"Patch {} v{} to v{}: Field {field} {message}.",
+			self.class, self.from_version, self.to_version
+		))
+	}
+}
+
+/// Upgraded definition, with the chain of patches applied to its own fields.
+struct Upgraded<'a> {
+	definition: Arc<Definition>,
+	chain: Vec<&'a Patch>,
+}
+
+/// Upgraded definitions, keyed by the original definition.
+type UpgradeCache<'a> = HashMap<*const Definition, Upgraded<'a>>;
+
+/// Collection of patches, which can be applied to upgrade documents.
+#[derive(Clone, Debug, Default)]
+pub struct Patcher {
+	patches: HashMap<(String, i32), Patch>,
+}
+
+impl Patcher {
+	/// Create a patcher with no patches.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add a patch, replacing any existing patch from the same class version.
+	pub fn add(&mut self, patch: Patch) {
+		self.patches
+			.insert((patch.class.clone(), patch.from_version), patch);
+	}
+
+	/// Upgrade every node of the walker's document, returning a walker for the
+	/// same node in the upgraded document. Patches are applied repeatedly to
+	/// each class until no patch remains for its version.
+	///
+	/// Node indices are unchanged by patching, so references between nodes
+	/// remain valid.
+	pub fn apply(&self, walker: &NodeWalker) -> Result<NodeWalker> {
+		let document = walker.document();
+
+		let mut upgraded = HashMap::new();
+		let definitions = document
+			.definitions()
+			.iter()
+			.map(|definition| self.upgrade_definition(definition, &mut upgraded))
+			.collect::<Result<Vec<_>>>()?;
+
+		let nodes = document
+			.nodes
+			.iter()
+			.map(|node| self.upgrade_node(node, &mut upgraded))
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(NodeWalker {
+			document: Arc::new(Document::new(document.root_index(), nodes, definitions)),
+			index: walker.index(),
+		})
+	}
+
+	/// Get the chain of patches to apply to a version of a class.
+	fn chain(&self, class: &str, version: i32) -> Result<Vec<&Patch>> {
+		let mut chain = Vec::new();
+		let mut visited = HashSet::new();
+		let mut version = version;
+		while let Some(patch) = self.patches.get(&(class.to_string(), version)) {
+			if !visited.insert(version) {
+				return Err(Error::Invalid(format!(
+					"Patches for {class} form a cycle at v{version}."
+				)));
+			}
+			chain.push(patch);
+			version = patch.to_version;
+		}
+		Ok(chain)
+	}
+
+	fn upgrade_definition<'a>(
+		&'a self,
+		definition: &Arc<Definition>,
+		upgraded: &mut UpgradeCache<'a>,
+	) -> Result<Arc<Definition>> {
+		if let Some(existing) = upgraded.get(&Arc::as_ptr(definition)) {
+			return Ok(existing.definition.clone());
+		}
+
+		let parent = definition
+			.parent()
+			.map(|parent| self.upgrade_definition(parent, upgraded))
+			.transpose()?;
+
+		let mut fields = definition
+			.own_fields()
+			.iter()
+			.map(|field| (field.clone(), None))
+			.collect();
+		let chain = self.chain(definition.name(), definition.version())?;
+		let mut version = definition.version();
+		for patch in &chain {
+			patch.apply(&mut fields)?;
+			version = patch.to_version;
+		}
+
+		let result = Arc::new(Definition::new(
+			definition.name().to_string(),
+			version,
+			parent,
+			fields.into_iter().map(|(field, _)| field).collect(),
+		));
+		upgraded.insert(
+			Arc::as_ptr(definition),
+			Upgraded {
+				definition: result.clone(),
+				chain,
+			},
+		);
+		Ok(result)
+	}
+
+	fn upgrade_node<'a>(&'a self, node: &Node, upgraded: &mut UpgradeCache<'a>) -> Result<Node> {
+		let definition = self.upgrade_definition(&node.definition, upgraded)?;
+
+		// Patches apply to the fields declared by each level of the inheritance
+		// chain, which are stored in order from the root-most parent.
+		let mut levels = Vec::new();
+		let mut current = Some(&node.definition);
+		while let Some(level) = current {
+			levels.push(level);
+			current = level.parent();
+		}
+
+		let mut values = node
+			.iter_fields()
+			.map(|(field, value)| (field.clone(), value.cloned()));
+		let mut fields = Vec::with_capacity(definition.fields().len());
+		for level in levels.into_iter().rev() {
+			let mut level_fields = values
+				.by_ref()
+				.take(level.own_fields().len())
+				.collect::<Vec<_>>();
+			// Every level was upgraded along with the node's definition.
+			for patch in &upgraded[&Arc::as_ptr(level)].chain {
+				patch.apply(&mut level_fields)?;
+			}
+			fields.extend(level_fields);
+		}
+
+		let field_mask = fields
+			.iter()
+			.map(|(_, value)| value.is_some())
+			.collect::<Vec<_>>();
+		let values = fields.into_iter().filter_map(|(_, value)| value).collect();
+		Ok(Node::new(definition, &field_mask, values))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		error::{Error, Result},
+		node::FieldKind,
+		testing::{node, versioned_definition, walker},
+		value::Value,
+	};
+
+	use super::{Patch, Patcher};
+
+	fn widen(value: &Value) -> Result<Value> {
+		let bytes = value
+			.as_vector()
+			.ok_or_else(|| Error::Invalid("Expected Vector.".into()))?;
+		Ok(Value::Vector(
+			bytes
+				.iter()
+				.map(|byte| Value::I32(i8::from_ne_bytes([*byte.as_u8().unwrap()]).into()))
+				.collect(),
+		))
+	}
+
+	#[test]
+	fn upgrade() {
+		let object = versioned_definition(
+			"hkReferencedObject",
+			0,
+			None,
+			vec![("memSize", FieldKind::Integer)],
+		);
+		let skeleton = versioned_definition(
+			"hkaSkeleton",
+			3,
+			Some(&object),
+			vec![
+				("name", FieldKind::String),
+				("parents", FieldKind::Vector(FieldKind::Byte.into())),
+				("legacy", FieldKind::Integer),
+				("next", FieldKind::Reference("hkaSkeleton".into())),
+			],
+		);
+		let walker = walker(vec![
+			node(
+				&skeleton,
+				vec![
+					Some(Value::I32(16)),
+					Some(Value::String("root".into())),
+					Some(Value::Vector(vec![Value::U8(255), Value::U8(0)])),
+					Some(Value::I32(1)),
+					Some(Value::Node(1)),
+				],
+			),
+			node(&skeleton, vec![None, None, None, None, None]),
+		]);
+
+		let mut patcher = Patcher::new();
+		patcher
+			.add(Patch::new("hkReferencedObject", 0, 1).rename_field("memSize", "memSizeAndFlags"));
+		patcher.add(
+			Patch::new("hkaSkeleton", 3, 4)
+				.rename_field("parents", "parentIndices")
+				.change_kind(
+					"parentIndices",
+					FieldKind::Vector(FieldKind::Integer.into()),
+					widen,
+				)
+				.remove_field("legacy"),
+		);
+		patcher.add(Patch::new("hkaSkeleton", 4, 5).add_field(
+			"floatSlots",
+			FieldKind::Vector(FieldKind::String.into()),
+			Some(Value::Vector(vec![])),
+		));
+
+		let upgraded = patcher.apply(&walker).unwrap();
+		assert_eq!(upgraded.version(), 5);
+		assert_eq!(upgraded.definition().parent().unwrap().version(), 1);
+
+		let names = upgraded
+			.definition()
+			.fields()
+			.iter()
+			.map(|field| field.name())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			names,
+			[
+				"memSizeAndFlags",
+				"name",
+				"parentIndices",
+				"next",
+				"floatSlots"
+			]
+		);
+
+		assert_eq!(upgraded.field("memSizeAndFlags"), Some(&Value::I32(16)));
+		assert_eq!(
+			upgraded.field("parentIndices"),
+			Some(&Value::Vector(vec![Value::I32(-1), Value::I32(0)]))
+		);
+		assert_eq!(upgraded.field("floatSlots"), Some(&Value::Vector(vec![])));
+
+		let next = upgraded
+			.resolve(upgraded.field("next").unwrap())
+			.unwrap()
+			.unwrap();
+		assert_eq!(next.index(), 1);
+		assert_eq!(next.version(), 5);
+		assert_eq!(next.field("name"), None);
+
+		let mut error = Patcher::new();
+		error.add(Patch::new("hkaSkeleton", 3, 4).remove_field("missing"));
+		assert_eq!(
+			error.apply(&walker).unwrap_err().to_string(),
+			"Invalid: Patch hkaSkeleton v3 to v4: Field missing does not exist."
+		);
+	}
+}
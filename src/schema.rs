@@ -0,0 +1,569 @@
+//! Database of known Havok class layouts, for detecting files with unexpected
+//! class versions.
+//!
+//! Typed code built on top of this crate generally assumes a specific layout
+//! for each class it reads. Checking a file's definitions against a [`Schema`]
+//! allows changes to the layouts used by game data, such as an SDK upgrade in
+//! a game patch, to be detected rather than silently misread.
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+	sync::OnceLock,
+};
+
+use crate::{document::Document, node::Definition};
+
+/// Layout of a single version of a class.
+#[derive(Debug)]
+pub struct KnownClass {
+	name: &'static str,
+	version: i32,
+	parent: Option<&'static str>,
+	fields: &'static [(&'static str, &'static str)],
+}
+
+impl KnownClass {
+	/// Create a class layout. Fields are listed as pairs of name and kind,
+	/// excluding inherited fields. Kinds are written in the display form of
+	/// [`FieldKind`](crate::FieldKind), i.e. `Vector<Struct<hkaBone>>`.
+	pub const fn new(
+		name: &'static str,
+		version: i32,
+		parent: Option<&'static str>,
+		fields: &'static [(&'static str, &'static str)],
+	) -> Self {
+		Self {
+			name,
+			version,
+			parent,
+			fields,
+		}
+	}
+
+	/// Get the class's name.
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+
+	/// Get the class's version.
+	pub fn version(&self) -> i32 {
+		self.version
+	}
+
+	/// Get the name of the class this class inherits from, if any.
+	pub fn parent(&self) -> Option<&'static str> {
+		self.parent
+	}
+
+	/// Get the fields declared by this class, as pairs of name and kind.
+	pub fn fields(&self) -> &'static [(&'static str, &'static str)] {
+		self.fields
+	}
+}
+
+/// Collection of known class layouts, which definitions can be checked against.
+///
+/// Classes that are not known to the schema at all are ignored by default, as
+/// files commonly contain classes that typed code never reads.
+#[derive(Clone, Debug, Default)]
+pub struct Schema {
+	classes: HashMap<&'static str, BTreeMap<i32, &'static KnownClass>>,
+	report_unknown_classes: bool,
+}
+
+impl Schema {
+	/// Create an empty schema.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Get the schema of common Havok classes provided by this crate, as written
+	/// by the SDK versions used by Final Fantasy XIV.
+	pub fn havok() -> &'static Schema {
+		static SCHEMA: OnceLock<Schema> = OnceLock::new();
+		SCHEMA.get_or_init(|| {
+			let mut schema = Schema::new();
+			for class in HAVOK_CLASSES {
+				schema.add(class);
+			}
+			schema
+		})
+	}
+
+	/// Set whether definitions of classes that are not known to the schema are
+	/// reported as [`MismatchKind::UnknownClass`].
+	pub fn report_unknown_classes(mut self, report: bool) -> Self {
+		self.report_unknown_classes = report;
+		self
+	}
+
+	/// Add a class layout to the schema, replacing any existing layout for the
+	/// same version of the class.
+	pub fn add(&mut self, class: &'static KnownClass) {
+		self.classes
+			.entry(class.name)
+			.or_default()
+			.insert(class.version, class);
+	}
+
+	/// Get the layout of the specified version of a class.
+	pub fn get(&self, name: &str, version: i32) -> Option<&'static KnownClass> {
+		self.classes.get(name)?.get(&version).copied()
+	}
+
+	/// Get all known versions of a class, in ascending order.
+	pub fn versions(&self, name: &str) -> Vec<i32> {
+		self.classes
+			.get(name)
+			.map(|versions| versions.keys().copied().collect())
+			.unwrap_or_default()
+	}
+
+	/// Check every definition in the document against the schema.
+	pub fn check(&self, document: &Document) -> Vec<Mismatch> {
+		document
+			.definitions()
+			.iter()
+			.flat_map(|definition| self.check_definition(definition))
+			.collect()
+	}
+
+	/// Check a single definition against the schema. Only fields declared by the
+	/// definition itself are compared; parent definitions should be checked
+	/// separately.
+	pub fn check_definition(&self, definition: &Definition) -> Vec<Mismatch> {
+		let mismatch = |kind| Mismatch {
+			class: definition.name().to_string(),
+			version: definition.version(),
+			kind,
+		};
+
+		let versions = self.versions(definition.name());
+		if versions.is_empty() {
+			return match self.report_unknown_classes {
+				true => vec![mismatch(MismatchKind::UnknownClass)],
+				false => Vec::new(),
+			};
+		}
+
+		let known = match self.get(definition.name(), definition.version()) {
+			Some(known) => known,
+			None => return vec![mismatch(MismatchKind::UnknownVersion { known: versions })],
+		};
+
+		let mut mismatches = Vec::new();
+
+		let parent = definition.parent().map(|parent| parent.name());
+		if parent != known.parent {
+			mismatches.push(mismatch(MismatchKind::Parent {
+				expected: known.parent.map(str::to_string),
+				actual: parent.map(str::to_string),
+			}));
+		}
+
+		let fields = definition.own_fields();
+		for (name, kind) in known.fields {
+			match fields.iter().find(|field| field.name() == *name) {
+				None => mismatches.push(mismatch(MismatchKind::MissingField {
+					name: name.to_string(),
+				})),
+				Some(field) => {
+					let actual = field.kind().to_string();
+					if actual != *kind {
+						mismatches.push(mismatch(MismatchKind::FieldKind {
+							name: name.to_string(),
+							expected: kind.to_string(),
+							actual,
+						}));
+					}
+				}
+			}
+		}
+
+		for field in fields {
+			if !known.fields.iter().any(|(name, _)| *name == field.name()) {
+				mismatches.push(mismatch(MismatchKind::UnexpectedField {
+					name: field.name().to_string(),
+				}));
+			}
+		}
+
+		mismatches
+	}
+}
+
+/// Difference between a definition and the known layouts of its class.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+	/// Name of the definition's class.
+	pub class: String,
+	/// Version of the definition.
+	pub version: i32,
+	/// Kind of mismatch found.
+	pub kind: MismatchKind,
+}
+
+/// Kind of [`Mismatch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MismatchKind {
+	/// The class is not known to the schema.
+	UnknownClass,
+	/// The class is known, but not at the definition's version.
+	UnknownVersion {
+		/// Versions of the class that are known.
+		known: Vec<i32>,
+	},
+	/// The definition inherits from a different class than expected.
+	Parent {
+		/// Expected parent class.
+		expected: Option<String>,
+		/// Parent class of the definition.
+		actual: Option<String>,
+	},
+	/// A known field is not declared by the definition.
+	MissingField {
+		/// Name of the field.
+		name: String,
+	},
+	/// The definition declares a field that is not known.
+	UnexpectedField {
+		/// Name of the field.
+		name: String,
+	},
+	/// A field is declared with a different kind than expected.
+	FieldKind {
+		/// Name of the field.
+		name: String,
+		/// Expected kind of the field.
+		expected: String,
+		/// Kind of the field in the definition.
+		actual: String,
+	},
+}
+
+impl fmt::Display for Mismatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} (v{}): ", self.class, self.version)?;
+		match &self.kind {
+			MismatchKind::UnknownClass => write!(f, "unknown class"),
+			MismatchKind::UnknownVersion { known } => {
+				let known = known
+					.iter()
+					.map(|version| format!("v{version}"))
+					.collect::<Vec<_>>()
+					.join(", ");
+				write!(f, "unknown version, known versions are {known}")
+			}
+			MismatchKind::Parent { expected, actual } => write!(
+				f,
+				"expected parent {}, got {}",
+				expected.as_deref().unwrap_or("none"),
+				actual.as_deref().unwrap_or("none")
+			),
+			MismatchKind::MissingField { name } => write!(f, "missing field {name}"),
+			MismatchKind::UnexpectedField { name } => write!(f, "unexpected field {name}"),
+			MismatchKind::FieldKind {
+				name,
+				expected,
+				actual,
+			} => write!(f, "expected field {name} to be {expected}, got {actual}"),
+		}
+	}
+}
+
+static HAVOK_CLASSES: &[KnownClass] = &[
+	KnownClass::new("hkBaseObject", 0, None, &[]),
+	KnownClass::new("hkReferencedObject", 0, Some("hkBaseObject"), &[]),
+	KnownClass::new(
+		"hkRootLevelContainer",
+		0,
+		None,
+		&[(
+			"namedVariants",
+			"Vector<Struct<hkRootLevelContainerNamedVariant>>",
+		)],
+	),
+	KnownClass::new(
+		"hkRootLevelContainerNamedVariant",
+		1,
+		None,
+		&[
+			("name", "String"),
+			("className", "String"),
+			("variant", "Reference<hkReferencedObject>"),
+		],
+	),
+	KnownClass::new(
+		"hkaAnimationContainer",
+		1,
+		Some("hkReferencedObject"),
+		&[
+			("skeletons", "Vector<Reference<hkaSkeleton>>"),
+			("animations", "Vector<Reference<hkaAnimation>>"),
+			("bindings", "Vector<Reference<hkaAnimationBinding>>"),
+			("attachments", "Vector<Reference<hkaBoneAttachment>>"),
+			("skins", "Vector<Reference<hkaMeshBinding>>"),
+		],
+	),
+	KnownClass::new(
+		"hkaSkeleton",
+		3,
+		Some("hkReferencedObject"),
+		&[
+			("name", "String"),
+			("parentIndices", "Vector<Integer>"),
+			("bones", "Vector<Struct<hkaBone>>"),
+			("referencePose", "Vector<Array<Float, 12>>"),
+			("referenceFloats", "Vector<Float>"),
+			("floatSlots", "Vector<String>"),
+			("localFrames", "Vector<Struct<hkaSkeletonLocalFrameOnBone>>"),
+		],
+	),
+	KnownClass::new(
+		"hkaSkeleton",
+		4,
+		Some("hkReferencedObject"),
+		&[
+			("name", "String"),
+			("parentIndices", "Vector<Integer>"),
+			("bones", "Vector<Struct<hkaBone>>"),
+			("referencePose", "Vector<Array<Float, 12>>"),
+			("referenceFloats", "Vector<Float>"),
+			("floatSlots", "Vector<String>"),
+			("localFrames", "Vector<Struct<hkaSkeletonLocalFrameOnBone>>"),
+			("partitions", "Vector<Struct<hkaSkeletonPartition>>"),
+		],
+	),
+	KnownClass::new(
+		"hkaSkeleton",
+		5,
+		Some("hkReferencedObject"),
+		&[
+			("name", "String"),
+			("parentIndices", "Vector<Integer>"),
+			("bones", "Vector<Struct<hkaBone>>"),
+			("referencePose", "Vector<Array<Float, 12>>"),
+			("referenceFloats", "Vector<Float>"),
+			("floatSlots", "Vector<String>"),
+			("localFrames", "Vector<Struct<hkaSkeletonLocalFrameOnBone>>"),
+			("partitions", "Vector<Struct<hkaSkeletonPartition>>"),
+		],
+	),
+	KnownClass::new(
+		"hkaBone",
+		0,
+		None,
+		&[("name", "String"), ("lockTranslation", "Byte")],
+	),
+	KnownClass::new(
+		"hkaSkeletonLocalFrameOnBone",
+		0,
+		None,
+		&[
+			("localFrame", "Reference<hkLocalFrame>"),
+			("boneIndex", "Integer"),
+		],
+	),
+	KnownClass::new(
+		"hkaSkeletonPartition",
+		1,
+		None,
+		&[
+			("name", "String"),
+			("startBoneIndex", "Integer"),
+			("numBones", "Integer"),
+		],
+	),
+	KnownClass::new(
+		"hkaAnimation",
+		3,
+		Some("hkReferencedObject"),
+		&[
+			("type", "Integer"),
+			("duration", "Float"),
+			("numberOfTransformTracks", "Integer"),
+			("numberOfFloatTracks", "Integer"),
+			("extractedMotion", "Reference<hkaAnimatedReferenceFrame>"),
+			("annotationTracks", "Vector<Struct<hkaAnnotationTrack>>"),
+		],
+	),
+	KnownClass::new(
+		"hkaAnnotationTrack",
+		0,
+		None,
+		&[
+			("trackName", "String"),
+			(
+				"annotations",
+				"Vector<Struct<hkaAnnotationTrackAnnotation>>",
+			),
+		],
+	),
+	KnownClass::new(
+		"hkaAnnotationTrackAnnotation",
+		0,
+		None,
+		&[("time", "Float"), ("text", "String")],
+	),
+	KnownClass::new(
+		"hkaSplineCompressedAnimation",
+		0,
+		Some("hkaAnimation"),
+		&[
+			("numFrames", "Integer"),
+			("numBlocks", "Integer"),
+			("maxFramesPerBlock", "Integer"),
+			("maskAndQuantizationSize", "Integer"),
+			("blockDuration", "Float"),
+			("blockInverseDuration", "Float"),
+			("frameDuration", "Float"),
+			("blockOffsets", "Vector<Integer>"),
+			("floatBlockOffsets", "Vector<Integer>"),
+			("transformOffsets", "Vector<Integer>"),
+			("floatOffsets", "Vector<Integer>"),
+			("data", "Vector<Byte>"),
+			("endian", "Integer"),
+		],
+	),
+	KnownClass::new(
+		"hkaInterleavedUncompressedAnimation",
+		0,
+		Some("hkaAnimation"),
+		&[
+			("transforms", "Vector<Array<Float, 12>>"),
+			("floats", "Vector<Float>"),
+		],
+	),
+	KnownClass::new(
+		"hkaAnimationBinding",
+		1,
+		Some("hkReferencedObject"),
+		&[
+			("originalSkeletonName", "String"),
+			("animation", "Reference<hkaAnimation>"),
+			("transformTrackToBoneIndices", "Vector<Integer>"),
+			("floatTrackToFloatSlotIndices", "Vector<Integer>"),
+			("blendHint", "Byte"),
+		],
+	),
+	KnownClass::new(
+		"hkaAnimationBinding",
+		3,
+		Some("hkReferencedObject"),
+		&[
+			("originalSkeletonName", "String"),
+			("animation", "Reference<hkaAnimation>"),
+			("transformTrackToBoneIndices", "Vector<Integer>"),
+			("floatTrackToFloatSlotIndices", "Vector<Integer>"),
+			("partitionIndices", "Vector<Integer>"),
+			("blendHint", "Byte"),
+		],
+	),
+];
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, skeleton, versioned_definition, walker},
+	};
+
+	use super::{Mismatch, MismatchKind, Schema};
+
+	#[test]
+	fn check() {
+		let (skeleton, bone) = skeleton(6, vec![]);
+		let partition = versioned_definition(
+			"hkaSkeletonPartition",
+			1,
+			None,
+			vec![
+				("name", FieldKind::String),
+				("startBoneIndex", FieldKind::Float),
+				("boneCount", FieldKind::Integer),
+			],
+		);
+		let unknown = definition("hkUnknown", Some(&bone), vec![]);
+		let walker = walker(vec![
+			node(&bone, vec![None, None]),
+			node(&skeleton, vec![None, None, None]),
+			node(&partition, vec![None, None, None]),
+			node(&unknown, vec![None, None]),
+		]);
+
+		let schema = Schema::havok();
+		assert!(schema.check_definition(&bone).is_empty());
+
+		let mismatches = schema.check(walker.document());
+		let mismatch = |class: &str, version, kind| Mismatch {
+			class: class.into(),
+			version,
+			kind,
+		};
+		assert_eq!(
+			mismatches,
+			[
+				mismatch(
+					"hkaSkeleton",
+					6,
+					MismatchKind::UnknownVersion {
+						known: vec![3, 4, 5]
+					}
+				),
+				mismatch(
+					"hkaSkeletonPartition",
+					1,
+					MismatchKind::FieldKind {
+						name: "startBoneIndex".into(),
+						expected: "Integer".into(),
+						actual: "Float".into(),
+					}
+				),
+				mismatch(
+					"hkaSkeletonPartition",
+					1,
+					MismatchKind::MissingField {
+						name: "numBones".into()
+					}
+				),
+				mismatch(
+					"hkaSkeletonPartition",
+					1,
+					MismatchKind::UnexpectedField {
+						name: "boneCount".into()
+					}
+				),
+			]
+		);
+
+		assert_eq!(
+			mismatches[0].to_string(),
+			"hkaSkeleton (v6): unknown version, known versions are v3, v4, v5"
+		);
+
+		let schema = schema.clone().report_unknown_classes(true);
+		assert_eq!(
+			schema.check_definition(&unknown),
+			[mismatch("hkUnknown", 0, MismatchKind::UnknownClass)]
+		);
+	}
+
+	#[test]
+	fn versions() {
+		let (older, _) = skeleton(3, vec![]);
+
+		let mismatches = Schema::havok().check_definition(&older);
+		assert!(mismatches.contains(&Mismatch {
+			class: "hkaSkeleton".into(),
+			version: 3,
+			kind: MismatchKind::MissingField {
+				name: "localFrames".into()
+			},
+		}));
+		assert!(!mismatches.iter().any(|mismatch| matches!(
+			&mismatch.kind,
+			MismatchKind::MissingField { name } if name == "partitions"
+		)));
+	}
+}
@@ -0,0 +1,218 @@
+use serde::{
+	ser::{SerializeMap, SerializeSeq, SerializeStruct},
+	Serialize, Serializer,
+};
+
+use crate::{
+	document::Document,
+	node::{Definition, Field, Node},
+	owned::{OwnedNode, OwnedTree},
+	value::{Value, NULL_NODE},
+};
+
+/// References serialize as a map of `{"$ref": index}`, such that graphs with
+/// cycles can be serialized safely. Null references serialize as none.
+impl Serialize for Value {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Self::U8(value) => serializer.serialize_u8(*value),
+			Self::I32(value) => serializer.serialize_i32(*value),
+			Self::F32(value) => serializer.serialize_f32(*value),
+			Self::String(value) => serializer.serialize_str(value),
+			Self::Node(NULL_NODE) => serializer.serialize_none(),
+			Self::Node(index) => {
+				let mut map = serializer.serialize_map(Some(1))?;
+				map.serialize_entry("$ref", index)?;
+				map.end()
+			}
+			Self::Vector(values) => {
+				let mut seq = serializer.serialize_seq(Some(values.len()))?;
+				for value in values {
+					seq.serialize_element(value)?;
+				}
+				seq.end()
+			}
+		}
+	}
+}
+
+/// Field kinds serialize as their display form, i.e. `Vector<Reference<hkaBone>>`.
+impl Serialize for Field {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("Field", 2)?;
+		state.serialize_field("name", self.name())?;
+		state.serialize_field("kind", &self.kind().to_string())?;
+		state.end()
+	}
+}
+
+/// Definitions serialize their own fields, with the parent struct by name.
+impl Serialize for Definition {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("Definition", 4)?;
+		state.serialize_field("name", self.name())?;
+		state.serialize_field("version", &self.version())?;
+		state.serialize_field("parent", &self.parent().map(|parent| parent.name()))?;
+		state.serialize_field("fields", self.own_fields())?;
+		state.end()
+	}
+}
+
+/// Documents serialize as a schema section containing every definition, and a
+/// list of all nodes with their stored fields. References between nodes are
+/// serialized as node indices, see the [`Value`] implementation for details.
+impl Serialize for Document {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("Document", 3)?;
+		state.serialize_field("root", &self.root_index())?;
+		state.serialize_field("definitions", &self.definitions)?;
+		let nodes = self
+			.nodes
+			.iter()
+			.enumerate()
+			.map(|(index, node)| SerializeNode(index, node))
+			.collect::<Vec<_>>();
+		state.serialize_field("nodes", &nodes)?;
+		state.end()
+	}
+}
+
+struct SerializeNode<'a>(usize, &'a Node);
+
+impl Serialize for SerializeNode<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let SerializeNode(index, node) = self;
+		let fields = node
+			.iter_fields()
+			.filter_map(|(field, value)| value.map(|value| (field.name(), value)))
+			.collect();
+
+		let mut state = serializer.serialize_struct("Node", 4)?;
+		state.serialize_field("id", index)?;
+		state.serialize_field("class", node.definition.name())?;
+		state.serialize_field("version", &node.definition.version())?;
+		state.serialize_field("fields", &SerializeFields(fields))?;
+		state.end()
+	}
+}
+
+struct SerializeFields<'a>(Vec<(&'a str, &'a Value)>);
+
+impl Serialize for SerializeFields<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_map(self.0.iter().copied())
+	}
+}
+
+/// Trees serialize as a list of their nodes, indexed by id. References within
+/// the tree are serialized as node ids.
+impl Serialize for OwnedTree {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("OwnedTree", 1)?;
+		state.serialize_field("nodes", self.nodes())?;
+		state.end()
+	}
+}
+
+impl Serialize for OwnedNode {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("OwnedNode", 3)?;
+		state.serialize_field("class", self.name())?;
+		state.serialize_field("version", &self.version())?;
+		state.serialize_field("fields", &SerializeFields(self.fields().collect()))?;
+		state.end()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use serde_json::json;
+
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, walker},
+		value::{Value, NULL_NODE},
+	};
+
+	#[test]
+	fn document() {
+		let base = definition("base", None, vec![("name", FieldKind::String)]);
+		let derived = definition(
+			"derived",
+			Some(&base),
+			vec![
+				("next", FieldKind::Reference("derived".into())),
+				("values", FieldKind::Vector(FieldKind::Byte.into())),
+			],
+		);
+		let walker = walker(vec![
+			node(
+				&derived,
+				vec![
+					Some(Value::String("first".into())),
+					Some(Value::Node(1)),
+					None,
+				],
+			),
+			node(
+				&derived,
+				vec![
+					None,
+					Some(Value::Node(NULL_NODE)),
+					Some(Value::Vector(vec![Value::U8(1), Value::U8(2)])),
+				],
+			),
+		]);
+
+		assert_eq!(
+			serde_json::to_value(walker.document()).unwrap(),
+			json!({
+				"root": 0,
+				"definitions": [
+					{
+						"name": "base",
+						"version": 0,
+						"parent": null,
+						"fields": [{"name": "name", "kind": "String"}],
+					},
+					{
+						"name": "derived",
+						"version": 0,
+						"parent": "base",
+						"fields": [
+							{"name": "next", "kind": "Reference<derived>"},
+							{"name": "values", "kind": "Vector<Byte>"},
+						],
+					},
+				],
+				"nodes": [
+					{
+						"id": 0,
+						"class": "derived",
+						"version": 0,
+						"fields": {"name": "first", "next": {"$ref": 1}},
+					},
+					{
+						"id": 1,
+						"class": "derived",
+						"version": 0,
+						"fields": {"next": null, "values": [1, 2]},
+					},
+				],
+			})
+		);
+
+		assert_eq!(
+			serde_json::to_value(walker.try_node(1).unwrap().detach()).unwrap(),
+			json!({
+				"nodes": [
+					{
+						"class": "derived",
+						"version": 0,
+						"fields": {"next": null, "values": [1, 2]},
+					},
+				],
+			})
+		);
+	}
+}
@@ -7,7 +7,7 @@ use super::tagfile::Tagfile;
 impl<R: Read> Tagfile<R> {
 	pub fn read_bitfield(&mut self, count: usize) -> Result<Vec<bool>> {
 		// Read enough bytes to cover the requested bitfield count.
-		let bytes = (count + 7) / 8;
+		let bytes = count.div_ceil(8);
 		let mut buffer = vec![];
 		self.reader
 			.by_ref()
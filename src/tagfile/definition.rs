@@ -1,4 +1,4 @@
-use std::{io::Read, rc::Rc};
+use std::{io::Read, sync::Arc};
 
 use crate::{
 	error::{Error, Result},
@@ -8,7 +8,7 @@ use crate::{
 use super::tagfile::Tagfile;
 
 impl<R: Read> Tagfile<R> {
-	pub fn read_definition(&mut self) -> Result<Rc<Definition>> {
+	pub fn read_definition(&mut self) -> Result<Arc<Definition>> {
 		let name = self.read_string()?;
 		let version = self.read_i32()?;
 
@@ -20,12 +20,7 @@ impl<R: Read> Tagfile<R> {
 			.map(|_index| self.read_field())
 			.collect::<Result<Vec<_>>>()?;
 
-		let definition = Rc::new(Definition {
-			name,
-			version,
-			parent,
-			fields,
-		});
+		let definition = Arc::new(Definition::new(name, version, parent, fields));
 
 		self.definitions.push(Some(definition.clone()));
 		Ok(definition)
@@ -1,4 +1,4 @@
-use std::{io::Read, rc::Rc};
+use std::{io::Read, sync::Arc};
 
 use crate::{
 	error::{Error, Result},
@@ -12,7 +12,7 @@ impl<R: Read> Tagfile<R> {
 	// TODO: what's the return type going to look like here? For consistency, it should probably act like a reference?
 	pub fn read_node(
 		&mut self,
-		definition: Option<Rc<Definition>>,
+		definition: Option<Arc<Definition>>,
 		store_reference: bool,
 	) -> Result<usize> {
 		// Default to storing the node at the end of the node array.
@@ -52,17 +52,13 @@ impl<R: Read> Tagfile<R> {
 		let fields = definition.fields();
 		let field_mask = self.read_bitfield(fields.len())?;
 		let values = fields
-			.into_iter()
+			.iter()
 			.zip(field_mask.iter())
 			.filter(|(_, stored)| **stored)
 			.map(|(field, _)| self.read_value(&field.kind))
 			.collect::<Result<Vec<_>>>()?;
 
-		self.nodes[node_index] = Some(Node {
-			definition,
-			field_mask,
-			values,
-		});
+		self.nodes[node_index] = Some(Node::new(definition, &field_mask, values));
 
 		Ok(node_index)
 	}
@@ -97,7 +93,7 @@ impl<R: Read> Tagfile<R> {
 
 			FieldKind::Vector(inner_kind) => {
 				let count = usize::try_from(self.read_i32()?).unwrap();
-				let values = self.read_value_vector(&*inner_kind, count)?;
+				let values = self.read_value_vector(inner_kind, count)?;
 				Ok(Value::Vector(values))
 			}
 
@@ -187,7 +183,7 @@ impl<R: Read> Tagfile<R> {
 				let fields = definition.fields();
 				let field_mask = self.read_bitfield(fields.len())?;
 				let values = fields
-					.into_iter()
+					.iter()
 					.zip(field_mask.iter())
 					.filter(|(_, stored)| **stored)
 					// TODO: non-float arrays should follow a different code path in the map here, i think
@@ -198,15 +194,15 @@ impl<R: Read> Tagfile<R> {
 				let nodes = (0..count)
 					.map(|index| {
 						let node_index = self.nodes.len();
-						self.nodes.push(Some(Node {
+						self.nodes.push(Some(Node::new(
 							// TODO: Not keen on the clone here but the structure makes it a bit hard. Other options?
-							definition: definition.clone(),
-							field_mask: field_mask.clone(),
-							values: values
+							definition.clone(),
+							&field_mask,
+							values
 								.iter()
 								.map(|field_values| field_values[index].clone())
 								.collect::<Vec<_>>(),
-						}));
+						)));
 						Value::Node(node_index)
 					})
 					.collect::<Vec<_>>();
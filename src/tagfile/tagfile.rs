@@ -1,8 +1,10 @@
-use std::{collections::HashMap, io::Read, rc::Rc};
+use std::{collections::HashMap, io::Read, sync::Arc};
 
 use crate::{
+	document::Document,
 	error::{Error, Result},
 	node::{Definition, Node},
+	value::NULL_NODE,
 	walker::NodeWalker,
 };
 
@@ -11,8 +13,17 @@ pub fn read(input: &mut impl Read) -> Result<NodeWalker> {
 	let mut tagfile = Tagfile::new(input);
 	let root_index = tagfile.read()?;
 	let nodes = tagfile.nodes.into_iter().flatten().collect();
+	let definitions = tagfile.definitions.into_iter().flatten().collect();
+	let document = Document::new(root_index, nodes, definitions);
+
+	if root_index >= document.nodes.len() {
+		return Err(Error::Invalid(format!(
+			"Root node index {root_index} out of range."
+		)));
+	}
+
 	Ok(NodeWalker {
-		nodes: Rc::new(nodes),
+		document: Arc::new(document),
 		index: root_index,
 	})
 }
@@ -26,7 +37,7 @@ pub struct Tagfile<R> {
 
 	// Caches
 	// TODO: The Option<>s here are to support empty case values - but there's realistically very few of those, and it complicates consumption a reasonable amount. Consider alternatives.
-	pub definitions: Vec<Option<Rc<Definition>>>,
+	pub definitions: Vec<Option<Arc<Definition>>>,
 	pub strings: Vec<Option<String>>,
 	pub references: Vec<usize>,
 	pub pending_references: HashMap<usize, usize>,
@@ -42,7 +53,7 @@ impl<R: Read> Tagfile<R> {
 
 			definitions: Vec::from([None]),
 			strings: Vec::from([Some("".into()), None]),
-			references: Vec::from([usize::MAX]),
+			references: Vec::from([NULL_NODE]),
 			pending_references: HashMap::new(),
 		}
 	}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use crate::{
+	document::Document,
+	math::{Matrix4, QsTransform, Vector4},
+	node::{Definition, Field, FieldKind, Node},
+	value::Value,
+	walker::NodeWalker,
+};
+
+/// Tolerance for comparing the results of float math.
+pub const EPSILON: f32 = 1e-5;
+
+pub fn definition(
+	name: &str,
+	parent: Option<&Arc<Definition>>,
+	fields: Vec<(&str, FieldKind)>,
+) -> Arc<Definition> {
+	versioned_definition(name, 0, parent, fields)
+}
+
+pub fn versioned_definition(
+	name: &str,
+	version: i32,
+	parent: Option<&Arc<Definition>>,
+	fields: Vec<(&str, FieldKind)>,
+) -> Arc<Definition> {
+	Arc::new(Definition::new(
+		name.into(),
+		version,
+		parent.cloned(),
+		fields
+			.into_iter()
+			.map(|(name, kind)| Field {
+				name: name.into(),
+				kind,
+			})
+			.collect(),
+	))
+}
+
+/// Build a node, with a `None` value marking a field that was not stored.
+pub fn node(definition: &Arc<Definition>, values: Vec<Option<Value>>) -> Node {
+	let field_mask = values.iter().map(Option::is_some).collect::<Vec<_>>();
+	Node::new(
+		definition.clone(),
+		&field_mask,
+		values.into_iter().flatten().collect(),
+	)
+}
+
+/// Build a walker over the provided nodes, with the first node as the root.
+pub fn walker(nodes: Vec<Node>) -> NodeWalker {
+	// Parents are listed before the definitions inheriting from them, as in files.
+	fn add(definitions: &mut Vec<Arc<Definition>>, definition: &Arc<Definition>) {
+		if definitions
+			.iter()
+			.any(|existing| Arc::ptr_eq(existing, definition))
+		{
+			return;
+		}
+		if let Some(parent) = definition.parent() {
+			add(definitions, parent);
+		}
+		definitions.push(definition.clone());
+	}
+
+	let mut definitions = Vec::new();
+	for node in &nodes {
+		add(&mut definitions, &node.definition);
+	}
+
+	NodeWalker {
+		document: Arc::new(Document::new(0, nodes, definitions)),
+		index: 0,
+	}
+}
+
+/// Build the definitions of `hkaSkeleton` and `hkaBone`. The skeleton declares
+/// its name, parent indices and bones, followed by the provided fields.
+pub fn skeleton(
+	version: i32,
+	fields: Vec<(&str, FieldKind)>,
+) -> (Arc<Definition>, Arc<Definition>) {
+	let bone = definition(
+		"hkaBone",
+		None,
+		vec![
+			("name", FieldKind::String),
+			("lockTranslation", FieldKind::Byte),
+		],
+	);
+	let skeleton = versioned_definition(
+		"hkaSkeleton",
+		version,
+		None,
+		[
+			("name", FieldKind::String),
+			(
+				"parentIndices",
+				FieldKind::Vector(FieldKind::Integer.into()),
+			),
+			(
+				"bones",
+				FieldKind::Vector(FieldKind::Struct("hkaBone".into()).into()),
+			),
+		]
+		.into_iter()
+		.chain(fields)
+		.collect(),
+	);
+	(skeleton, bone)
+}
+
+/// Build the value of a transform field, translated along the x axis.
+pub fn transform(x: f32) -> Value {
+	let mut transform = QsTransform::IDENTITY;
+	transform.translation = Vector4::new(x, 0., 0., 0.);
+	Value::Vector(transform.to_array().into_iter().map(Value::F32).collect())
+}
+
+/// Assert that two vectors are equal, within a tolerance.
+pub fn assert_near(left: Vector4, right: Vector4, epsilon: f32) {
+	assert!(
+		(left - right).length4() < epsilon,
+		"Expected {right:?}, got {left:?}."
+	);
+}
+
+/// Assert that every column of two matrices is equal, within a tolerance.
+pub fn assert_matrix_near(left: Matrix4, right: Matrix4, epsilon: f32) {
+	for (left, right) in left.columns.into_iter().zip(right.columns) {
+		assert_near(left, right, epsilon);
+	}
+}
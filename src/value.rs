@@ -1,9 +1,14 @@
+use std::borrow::Cow;
+
 use enum_as_inner::EnumAsInner;
 use thiserror::Error;
 
+/// Node index used by reference values that do not point to any node.
+pub(crate) const NULL_NODE: usize = usize::MAX;
+
 /// Value of a field in a Node.
 #[allow(missing_docs)]
-#[derive(Clone, Debug, EnumAsInner)]
+#[derive(Clone, Debug, EnumAsInner, PartialEq)]
 pub enum Value {
 	U8(u8),
 	I32(i32),
@@ -13,21 +18,36 @@ pub enum Value {
 	Vector(Vec<Value>),
 }
 
+/// Error returned when a value cannot be converted to the requested type.
 #[derive(Debug, Error)]
 #[error("Expected {expected}, got {value:?}.")]
 pub struct TryFromValueError {
 	value: Value,
-	expected: &'static str,
+	expected: Cow<'static, str>,
+}
+
+impl TryFromValueError {
+	pub(crate) fn new(value: &Value, expected: impl Into<Cow<'static, str>>) -> Self {
+		Self {
+			value: value.clone(),
+			expected: expected.into(),
+		}
+	}
+
+	/// Get the value that failed to convert.
+	pub fn value(&self) -> &Value {
+		&self.value
+	}
 }
 
 impl TryFrom<&Value> for u8 {
 	type Error = TryFromValueError;
 
 	fn try_from(value: &Value) -> Result<Self, Self::Error> {
-		value.as_u8().cloned().ok_or(TryFromValueError {
-			value: value.clone(),
-			expected: "U8",
-		})
+		value
+			.as_u8()
+			.cloned()
+			.ok_or_else(|| TryFromValueError::new(value, "U8"))
 	}
 }
 
@@ -35,10 +55,10 @@ impl TryFrom<&Value> for i32 {
 	type Error = TryFromValueError;
 
 	fn try_from(value: &Value) -> Result<Self, Self::Error> {
-		value.as_i32().cloned().ok_or(TryFromValueError {
-			value: value.clone(),
-			expected: "I32",
-		})
+		value
+			.as_i32()
+			.cloned()
+			.ok_or_else(|| TryFromValueError::new(value, "I32"))
 	}
 }
 
@@ -46,10 +66,10 @@ impl TryFrom<&Value> for f32 {
 	type Error = TryFromValueError;
 
 	fn try_from(value: &Value) -> Result<Self, Self::Error> {
-		value.as_f32().cloned().ok_or(TryFromValueError {
-			value: value.clone(),
-			expected: "F32",
-		})
+		value
+			.as_f32()
+			.cloned()
+			.ok_or_else(|| TryFromValueError::new(value, "F32"))
 	}
 }
 
@@ -57,10 +77,10 @@ impl TryFrom<&Value> for String {
 	type Error = TryFromValueError;
 
 	fn try_from(value: &Value) -> Result<Self, Self::Error> {
-		value.as_string().cloned().ok_or(TryFromValueError {
-			value: value.clone(),
-			expected: "String",
-		})
+		value
+			.as_string()
+			.cloned()
+			.ok_or_else(|| TryFromValueError::new(value, "String"))
 	}
 }
 
@@ -73,12 +93,178 @@ where
 	fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
 		value
 			.as_vector()
-			.ok_or(TryFromValueError {
-				value: value.clone(),
-				expected: "Vector",
-			})?
+			.ok_or_else(|| TryFromValueError::new(value, "Vector"))?
 			.iter()
 			.map(|x| T::try_from(x))
 			.collect()
 	}
 }
+
+/// Havok booleans are stored as bytes, with any non-zero value being `true`.
+impl TryFrom<&Value> for bool {
+	type Error = TryFromValueError;
+
+	fn try_from(value: &Value) -> Result<Self, Self::Error> {
+		u8::try_from(value).map(|value| value != 0)
+	}
+}
+
+// Integers narrower or wider than those stored are range checked, and may be
+// converted from either integer representation.
+macro_rules! impl_try_from_integer {
+	($($type:ty),+ $(,)?) => {$(
+		impl TryFrom<&Value> for $type {
+			type Error = TryFromValueError;
+
+			fn try_from(value: &Value) -> Result<Self, Self::Error> {
+				let converted = match value {
+					Value::U8(integer) => <$type>::try_from(*integer).ok(),
+					Value::I32(integer) => <$type>::try_from(*integer).ok(),
+					_ => None,
+				};
+				converted.ok_or_else(|| TryFromValueError::new(value, stringify!($type)))
+			}
+		}
+	)+};
+}
+
+impl_try_from_integer!(u16, i16, u32, usize);
+
+impl<'a> TryFrom<&'a Value> for &'a str {
+	type Error = TryFromValueError;
+
+	fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+		value
+			.as_string()
+			.map(String::as_str)
+			.ok_or_else(|| TryFromValueError::new(value, "String"))
+	}
+}
+
+impl<'a, T, const N: usize> TryFrom<&'a Value> for [T; N]
+where
+	T: TryFrom<&'a Value, Error = TryFromValueError>,
+{
+	type Error = TryFromValueError;
+
+	fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+		Vec::<T>::try_from(value)?
+			.try_into()
+			.map_err(|_| TryFromValueError::new(value, format!("Vector of length {N}")))
+	}
+}
+
+impl<'a, T> TryFrom<&'a Value> for Box<[T]>
+where
+	T: TryFrom<&'a Value, Error = TryFromValueError>,
+{
+	type Error = TryFromValueError;
+
+	fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+		Vec::<T>::try_from(value).map(Vec::into_boxed_slice)
+	}
+}
+
+/// Null references convert to `None`. Other values are always `Some`.
+impl<'a, T> TryFrom<&'a Value> for Option<T>
+where
+	T: TryFrom<&'a Value, Error = TryFromValueError>,
+{
+	type Error = TryFromValueError;
+
+	fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+		match value {
+			Value::Node(NULL_NODE) => Ok(None),
+			value => T::try_from(value).map(Some),
+		}
+	}
+}
+
+// Tuples convert from vectors with exactly as many elements as the tuple.
+macro_rules! impl_try_from_tuple {
+	($($length:literal => ($($type:ident),+)),+ $(,)?) => {$(
+		impl<'a, $($type),+> TryFrom<&'a Value> for ($($type,)+)
+		where
+			$($type: TryFrom<&'a Value, Error = TryFromValueError>),+
+		{
+			type Error = TryFromValueError;
+
+			fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+				let error = || TryFromValueError::new(value, concat!("Vector of length ", $length));
+				match value.as_vector() {
+					Some(values) if values.len() == $length => {
+						let mut values = values.iter();
+						Ok(($($type::try_from(values.next().ok_or_else(error)?)?,)+))
+					}
+					_ => Err(error()),
+				}
+			}
+		}
+	)+};
+}
+
+impl_try_from_tuple!(
+	1 => (A),
+	2 => (A, B),
+	3 => (A, B, C),
+	4 => (A, B, C, D),
+);
+
+#[cfg(test)]
+mod test {
+	use super::{Value, NULL_NODE};
+
+	fn floats(values: &[f32]) -> Value {
+		Value::Vector(values.iter().copied().map(Value::F32).collect())
+	}
+
+	#[test]
+	fn scalars() {
+		assert!(bool::try_from(&Value::U8(2)).unwrap());
+		assert!(!bool::try_from(&Value::U8(0)).unwrap());
+		assert_eq!(u16::try_from(&Value::I32(65535)).unwrap(), 65535);
+		assert_eq!(u32::try_from(&Value::U8(7)).unwrap(), 7);
+		assert_eq!(i16::try_from(&Value::I32(-5)).unwrap(), -5);
+		assert_eq!(usize::try_from(&Value::I32(3)).unwrap(), 3);
+
+		let value = Value::String("hkaSkeleton".into());
+		assert_eq!(<&str>::try_from(&value).unwrap(), "hkaSkeleton");
+
+		assert_eq!(
+			u16::try_from(&Value::I32(65536)).unwrap_err().to_string(),
+			"Expected u16, got I32(65536)."
+		);
+		assert_eq!(
+			usize::try_from(&Value::I32(-1)).unwrap_err().to_string(),
+			"Expected usize, got I32(-1)."
+		);
+	}
+
+	#[test]
+	fn compound() {
+		let value = floats(&[0., 1., 2., 3.]);
+		assert_eq!(<[f32; 4]>::try_from(&value).unwrap(), [0., 1., 2., 3.]);
+		assert_eq!(
+			<Box<[f32]>>::try_from(&value).unwrap().as_ref(),
+			[0., 1., 2., 3.]
+		);
+		assert_eq!(
+			<(f32, f32, f32, f32)>::try_from(&value).unwrap(),
+			(0., 1., 2., 3.)
+		);
+		assert_eq!(
+			<[f32; 3]>::try_from(&value).unwrap_err().to_string(),
+			format!("Expected Vector of length 3, got {value:?}.")
+		);
+		assert!(<(f32, f32)>::try_from(&value).is_err());
+
+		let nested = Value::Vector(vec![value.clone(), value]);
+		assert_eq!(<[[f32; 4]; 2]>::try_from(&nested).unwrap()[1][3], 3.);
+
+		assert_eq!(
+			Option::<i32>::try_from(&Value::Node(NULL_NODE)).unwrap(),
+			None
+		);
+		assert_eq!(Option::<i32>::try_from(&Value::I32(1)).unwrap(), Some(1));
+	}
+}
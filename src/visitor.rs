@@ -0,0 +1,264 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{node::Field, value::Value, walker::NodeWalker};
+
+/// Callbacks for walking a graph of nodes. All methods have default
+/// implementations, so implementors need only handle what they are interested in.
+///
+/// Each node is entered at most once per walk. Later references to a node that
+/// has already been entered are reported via [`revisit_node`](Self::revisit_node),
+/// which also prevents cycles from being followed indefinitely.
+pub trait Visitor {
+	/// Called when a node is reached that has not yet been entered. Returning
+	/// `false` will skip the node's fields, as well as the matching call to
+	/// [`leave_node`](Self::leave_node). Skipped nodes are not considered entered,
+	/// so later references to them call this again.
+	fn enter_node(&mut self, _node: &NodeWalker, _path: &Path) -> bool {
+		true
+	}
+
+	/// Called after all of a node's fields have been visited.
+	fn leave_node(&mut self, _node: &NodeWalker, _path: &Path) {}
+
+	/// Called when a reference is reached to a node that has already been entered.
+	fn revisit_node(&mut self, _node: &NodeWalker, _path: &Path) {}
+
+	/// Called for each stored field of a node, before its value is visited.
+	/// Returning `false` will skip the field's value.
+	fn visit_field(&mut self, _node: &NodeWalker, _field: &Field, _path: &Path) -> bool {
+		true
+	}
+
+	/// Called for each value, including the elements of vectors. Returning
+	/// `false` will skip any nodes or elements contained by the value.
+	fn visit_value(&mut self, _value: &Value, _path: &Path) -> bool {
+		true
+	}
+}
+
+/// Single step along a [`Path`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+	/// Named field of a node.
+	Field(String),
+	/// Element of a vector.
+	Index(usize),
+}
+
+/// Path from the node a walk started at to the item currently being visited.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Path {
+	segments: Vec<PathSegment>,
+}
+
+impl Path {
+	/// Get the segments of the path, from the starting node outward.
+	pub fn segments(&self) -> &[PathSegment] {
+		&self.segments
+	}
+
+	/// Check if this path refers to the node the walk started at.
+	pub fn is_empty(&self) -> bool {
+		self.segments.is_empty()
+	}
+}
+
+impl fmt::Display for Path {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (index, segment) in self.segments.iter().enumerate() {
+			match segment {
+				PathSegment::Field(name) if index == 0 => write!(f, "{name}")?,
+				PathSegment::Field(name) => write!(f, ".{name}")?,
+				PathSegment::Index(index) => write!(f, "[{index}]")?,
+			}
+		}
+		Ok(())
+	}
+}
+
+pub fn walk(walker: &NodeWalker, visitor: &mut impl Visitor) {
+	let mut driver = Driver {
+		visitor,
+		path: Path::default(),
+		visited: HashSet::new(),
+	};
+	driver.walk_node(walker);
+}
+
+struct Driver<'a, V> {
+	visitor: &'a mut V,
+	path: Path,
+	visited: HashSet<usize>,
+}
+
+impl<V: Visitor> Driver<'_, V> {
+	fn walk_node(&mut self, walker: &NodeWalker) {
+		if self.visited.contains(&walker.index()) {
+			self.visitor.revisit_node(walker, &self.path);
+			return;
+		}
+
+		if !self.visitor.enter_node(walker, &self.path) {
+			return;
+		}
+		self.visited.insert(walker.index());
+
+		for (field, value) in walker.current().iter_fields() {
+			let value = match value {
+				Some(value) => value,
+				None => continue,
+			};
+
+			self.path
+				.segments
+				.push(PathSegment::Field(field.name().to_string()));
+			if self.visitor.visit_field(walker, field, &self.path) {
+				self.walk_value(walker, value);
+			}
+			self.path.segments.pop();
+		}
+
+		self.visitor.leave_node(walker, &self.path);
+	}
+
+	fn walk_value(&mut self, walker: &NodeWalker, value: &Value) {
+		if !self.visitor.visit_value(value, &self.path) {
+			return;
+		}
+
+		match value {
+			Value::Node(..) => {
+				if let Some(node) = walker.resolve(value).ok().flatten() {
+					self.walk_node(&node);
+				}
+			}
+
+			Value::Vector(values) => {
+				for (index, value) in values.iter().enumerate() {
+					self.path.segments.push(PathSegment::Index(index));
+					self.walk_value(walker, value);
+					self.path.segments.pop();
+				}
+			}
+
+			_ => {}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, walker},
+		value::Value,
+		walker::NodeWalker,
+	};
+
+	use super::{Path, Visitor};
+
+	/// Records every callback, skipping the node with the index provided.
+	#[derive(Default)]
+	struct Recorder(Vec<String>, Option<usize>);
+
+	impl Visitor for Recorder {
+		fn enter_node(&mut self, node: &NodeWalker, path: &Path) -> bool {
+			self.0.push(format!("enter #{} at '{path}'", node.index()));
+			self.1 != Some(node.index())
+		}
+
+		fn leave_node(&mut self, node: &NodeWalker, _path: &Path) {
+			self.0.push(format!("leave #{}", node.index()));
+		}
+
+		fn revisit_node(&mut self, node: &NodeWalker, path: &Path) {
+			self.0
+				.push(format!("revisit #{} at '{path}'", node.index()));
+		}
+	}
+
+	#[test]
+	fn walk() {
+		let base = definition(
+			"base",
+			None,
+			vec![
+				(
+					"children",
+					FieldKind::Vector(FieldKind::Reference("base".into()).into()),
+				),
+				("parent", FieldKind::Reference("base".into())),
+			],
+		);
+		let walker = walker(vec![
+			node(
+				&base,
+				vec![
+					Some(Value::Vector(vec![Value::Node(1), Value::Node(2)])),
+					None,
+				],
+			),
+			node(&base, vec![None, Some(Value::Node(0))]),
+			node(&base, vec![None, Some(Value::Node(0))]),
+		]);
+
+		let mut recorder = Recorder::default();
+		walker.walk(&mut recorder);
+
+		assert_eq!(
+			recorder.0,
+			[
+				"enter #0 at ''",
+				"enter #1 at 'children[0]'",
+				"revisit #0 at 'children[0].parent'",
+				"leave #1",
+				"enter #2 at 'children[1]'",
+				"revisit #0 at 'children[1].parent'",
+				"leave #2",
+				"leave #0",
+			]
+		);
+	}
+
+	#[test]
+	fn skip() {
+		let base = definition(
+			"base",
+			None,
+			vec![
+				(
+					"children",
+					FieldKind::Vector(FieldKind::Reference("base".into()).into()),
+				),
+				("sibling", FieldKind::Reference("base".into())),
+			],
+		);
+		let walker = walker(vec![
+			node(
+				&base,
+				vec![
+					Some(Value::Vector(vec![Value::Node(1), Value::Node(2)])),
+					None,
+				],
+			),
+			node(&base, vec![None, Some(Value::Node(2))]),
+			node(&base, vec![None, Some(Value::Node(1))]),
+		]);
+
+		// Node 1 is skipped, so is offered again rather than revisited.
+		let mut recorder = Recorder(Vec::new(), Some(1));
+		walker.walk(&mut recorder);
+
+		assert_eq!(
+			recorder.0,
+			[
+				"enter #0 at ''",
+				"enter #1 at 'children[0]'",
+				"enter #2 at 'children[1]'",
+				"enter #1 at 'children[1].sibling'",
+				"leave #2",
+				"leave #0",
+			]
+		);
+	}
+}
@@ -1,28 +1,110 @@
-use std::{fmt, rc::Rc};
+use std::{borrow::Cow, fmt, sync::Arc};
 
 use crate::{
-	node::{Field, Node},
-	value::Value,
+	diff::{diff, DiffOptions, Difference},
+	document::Document,
+	dump::Dump,
+	error::{Error, Result},
+	from_node::FromField,
+	hash::content_hash,
+	node::{Definition, Node},
+	owned::OwnedTree,
+	value::{Value, NULL_NODE},
+	visitor::{walk, Visitor},
 };
 
 /// View into a collection of nodes.
+///
+/// A walker will only ever point to a node that exists within its collection,
+/// and all navigation between nodes is checked. The underlying collection is
+/// immutable and shared, so walkers are cheap to clone and may be sent between
+/// threads.
+#[derive(Clone)]
 pub struct NodeWalker {
-	pub(super) nodes: Rc<Vec<Node>>,
+	pub(super) document: Arc<Document>,
 	pub(super) index: usize,
 }
 
 impl NodeWalker {
-	/// Get a walker instance for the requested node index.
-	pub fn node(&self, index: usize) -> NodeWalker {
-		// TODO: sanity check index.
+	/// Get a walker instance for the requested node index, if it exists.
+	pub fn node(&self, index: usize) -> Option<NodeWalker> {
+		match index < self.document.nodes.len() {
+			true => Some(self.node_unchecked(index)),
+			false => None,
+		}
+	}
+
+	/// Get a walker instance for the requested node index, failing if it does
+	/// not exist.
+	pub fn try_node(&self, index: usize) -> Result<NodeWalker> {
+		self.node(index).ok_or_else(|| {
+			Error::Invalid(format!(
+				"Node index {index} out of range for {} nodes.",
+				self.document.nodes.len()
+			))
+		})
+	}
+
+	/// Get a walker instance for the node referenced by a value. Null references
+	/// resolve to `None`, while values that are not references, or reference a
+	/// node that does not exist, will fail.
+	pub fn resolve(&self, value: &Value) -> Result<Option<NodeWalker>> {
+		match value {
+			Value::Node(NULL_NODE) => Ok(None),
+			Value::Node(index) => self.try_node(*index).map(Some),
+			other => Err(Error::Invalid(format!(
+				"Expected node reference, got {other:?}."
+			))),
+		}
+	}
+
+	// Callers are expected to only provide indices that are known to be valid.
+	// Reference values within a document are known to be valid, anything else
+	// can only be a null reference, so walking code may treat a reference that
+	// fails to resolve as null.
+	fn node_unchecked(&self, index: usize) -> NodeWalker {
 		NodeWalker {
-			nodes: self.nodes.clone(),
+			document: self.document.clone(),
 			index,
 		}
 	}
 
-	fn current(&self) -> &Node {
-		&self.nodes[self.index]
+	/// Iterate over every node in the collection, in index order.
+	pub fn all_nodes(&self) -> impl Iterator<Item = NodeWalker> + '_ {
+		(0..self.document.nodes.len()).map(|index| self.node_unchecked(index))
+	}
+
+	/// Find every node in the collection with the specified struct name, in index
+	/// order. If `include_subclasses` is set, nodes of structs inheriting from the
+	/// named struct will also be included.
+	pub fn find_by_class(
+		&self,
+		name: &str,
+		include_subclasses: bool,
+	) -> impl Iterator<Item = NodeWalker> + '_ {
+		self.document
+			.class_indices(name, include_subclasses)
+			.into_iter()
+			.map(|index| self.node_unchecked(index))
+	}
+
+	pub(crate) fn current(&self) -> &Node {
+		&self.document.nodes[self.index]
+	}
+
+	/// Get the document containing the current node.
+	pub fn document(&self) -> &Document {
+		&self.document
+	}
+
+	/// Get the current node's struct definition.
+	pub fn definition(&self) -> &Arc<Definition> {
+		&self.current().definition
+	}
+
+	/// Get the current node's index within the collection.
+	pub fn index(&self) -> usize {
+		self.index
 	}
 
 	/// Get the current node's struct name.
@@ -35,27 +117,140 @@ impl NodeWalker {
 		self.current().definition.version
 	}
 
-	fn iter_fields(&self) -> impl Iterator<Item = (&Field, Option<usize>)> {
+	/// Check if the current node's struct is, or inherits from, the specified struct.
+	pub fn is_a(&self, name: &str) -> bool {
+		self.current().definition.is_a(name)
+	}
+
+	/// Iterate over every field in the collection that references the current
+	/// node, as tuples of the referring node, the name of the referring field,
+	/// and the index of the reference within the field if it is a vector. The
+	/// reverse reference index will be built on first use.
+	pub fn referrers(&self) -> impl Iterator<Item = (NodeWalker, &str, Option<usize>)> + '_ {
+		self.document.referrers(self.index).iter().map(|referrer| {
+			let node = &self.document.nodes[referrer.node];
+			let field = &node.definition.fields()[referrer.field].name;
+			(
+				self.node_unchecked(referrer.node),
+				field.as_str(),
+				referrer.element,
+			)
+		})
+	}
+
+	/// Get a human-readable tree rendering of the current node and the nodes
+	/// reachable from it. This is equivalent to the `Display` implementation,
+	/// with additional configuration.
+	pub fn dump(&self) -> Dump<'_> {
+		Dump::new(self)
+	}
+
+	/// Copy the current node and every node reachable from it into a
+	/// self-contained tree, independent of the source document.
+	pub fn detach(&self) -> OwnedTree {
+		OwnedTree::new(self)
+	}
+
+	/// Structurally compare the current node against another node, which may be
+	/// from a different document. References are followed, and each pair of
+	/// nodes is compared at most once.
+	pub fn diff(&self, other: &NodeWalker, options: &DiffOptions) -> Vec<Difference> {
+		diff(self, other, options)
+	}
+
+	/// Compute a stable hash of the content of the current node and every node
+	/// reachable from it. The hash only depends on the decoded structs and values,
+	/// and is independent of node storage order and file layout details such as
+	/// string caching, so identical content from different files hashes equally.
+	pub fn content_hash(&self) -> u64 {
+		content_hash(self)
+	}
+
+	/// Walk the graph of nodes reachable from the current node, calling back into
+	/// the provided visitor. Each node will be entered at most once.
+	pub fn walk(&self, visitor: &mut impl Visitor) {
+		walk(self, visitor)
+	}
+
+	/// Deserialize the current node into the requested type. See the
+	/// [`Deserializer`](serde::Deserializer) implementation for details.
+	#[cfg(feature = "serde")]
+	pub fn deserialize<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+		T::deserialize(self)
+	}
+
+	/// Look up the specified field, distinguishing between fields that do not
+	/// exist on the current node's struct, and fields that were not stored.
+	pub fn lookup(&self, name: &str) -> FieldLookup<'_> {
 		let current = self.current();
-		let mask_indexes = current.field_mask.iter().scan(0usize, |index, mask| {
-			Some(match mask {
-				true => {
-					let id = *index;
-					*index += 1;
-					Some(id)
-				}
-				false => None,
-			})
-		});
+		let slot = match current.definition.slot(name) {
+			Some(slot) => slot,
+			None => return FieldLookup::NoSuchField,
+		};
 
-		std::iter::zip(current.definition.fields(), mask_indexes)
+		match current.value(slot) {
+			Some(value) => FieldLookup::Present(value),
+			None => FieldLookup::NotStored,
+		}
 	}
 
 	/// Get the value of the specified field.
 	pub fn field(&self, name: &str) -> Option<&Value> {
-		self.iter_fields()
-			.find(|(field, _index)| field.name == name)
-			.and_then(|(_field, index)| index.map(|index| &self.current().values[index]))
+		self.lookup(name).value()
+	}
+
+	/// Get the value of the specified field, converted to the requested type.
+	/// Fields that were not stored are converted from their default value, and
+	/// fail if their kind has none, see
+	/// [`FieldKind::default_value`](crate::FieldKind::default_value).
+	pub fn get<T: FromField>(&self, name: &str) -> Result<T> {
+		let value = self.field_or_default(name).ok_or_else(|| {
+			let message = match self.lookup(name) {
+				FieldLookup::NoSuchField => "Missing field.",
+				_ => "Field was not stored, and has no default value.",
+			};
+			Error::Invalid(format!("{}.{name}: {message}", self.name()))
+		})?;
+		T::from_field(self, &value)
+			.map_err(|error| Error::Invalid(format!("{}.{name}: {error}", self.name())))
+	}
+
+	/// Get the value of the specified field, falling back to the zero value of
+	/// the field's kind if it was not stored. Returns `None` if the field does
+	/// not exist, or its kind has no default value, as for struct fields.
+	pub fn field_or_default(&self, name: &str) -> Option<Cow<'_, Value>> {
+		let current = self.current();
+		let slot = current.definition.slot(name)?;
+
+		match current.value(slot) {
+			Some(value) => Some(Cow::Borrowed(value)),
+			None => current.definition.fields()[slot]
+				.kind
+				.default_value()
+				.map(Cow::Owned),
+		}
+	}
+}
+
+/// Result of looking up a field on a node.
+#[derive(Clone, Copy, Debug)]
+pub enum FieldLookup<'a> {
+	/// The node's struct has no field with the requested name.
+	NoSuchField,
+	/// The field exists, but no value was stored for it. Havok omits fields that
+	/// hold their default value.
+	NotStored,
+	/// The field exists and has a stored value.
+	Present(&'a Value),
+}
+
+impl<'a> FieldLookup<'a> {
+	/// Get the stored value, if any.
+	pub fn value(self) -> Option<&'a Value> {
+		match self {
+			Self::Present(value) => Some(value),
+			Self::NoSuchField | Self::NotStored => None,
+		}
 	}
 }
 
@@ -63,7 +258,187 @@ impl fmt::Debug for NodeWalker {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("NodeWalker")
 			.field("index", &self.index)
-			.field("node", &self.nodes[self.index])
+			.field("name", &self.name())
+			.field("version", &self.version())
 			.finish()
 	}
 }
+
+impl fmt::Display for NodeWalker {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.dump().fmt(f)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		node::FieldKind,
+		testing::{definition, node, walker},
+		value::{Value, NULL_NODE},
+	};
+
+	use super::{FieldLookup, NodeWalker};
+
+	fn indices(walkers: impl Iterator<Item = NodeWalker>) -> Vec<usize> {
+		walkers.map(|walker| walker.index()).collect()
+	}
+
+	#[test]
+	fn all_nodes() {
+		let base = definition("base", None, vec![]);
+		let walker = walker(vec![
+			node(&base, vec![]),
+			node(&base, vec![]),
+			node(&base, vec![]),
+		]);
+		assert_eq!(indices(walker.all_nodes()), [0, 1, 2]);
+	}
+
+	#[test]
+	fn find_by_class() {
+		let base = definition("base", None, vec![]);
+		let derived = definition("derived", Some(&base), vec![]);
+		let other = definition("other", None, vec![]);
+		let walker = walker(vec![
+			node(&derived, vec![]),
+			node(&base, vec![]),
+			node(&other, vec![]),
+			node(&derived, vec![]),
+		]);
+
+		assert_eq!(indices(walker.find_by_class("base", false)), [1]);
+		assert_eq!(indices(walker.find_by_class("base", true)), [0, 1, 3]);
+		assert_eq!(indices(walker.find_by_class("derived", true)), [0, 3]);
+		assert_eq!(
+			indices(walker.find_by_class("missing", true)),
+			[] as [usize; 0]
+		);
+	}
+
+	#[test]
+	fn referrers() {
+		let leaf = definition("leaf", None, vec![]);
+		let root = definition(
+			"root",
+			None,
+			vec![
+				("unset", FieldKind::Reference("leaf".into())),
+				("single", FieldKind::Reference("leaf".into())),
+				(
+					"many",
+					FieldKind::Vector(FieldKind::Reference("leaf".into()).into()),
+				),
+			],
+		);
+		let walker = walker(vec![
+			node(
+				&root,
+				vec![
+					None,
+					Some(Value::Node(1)),
+					Some(Value::Vector(vec![Value::Node(2), Value::Node(1)])),
+				],
+			),
+			node(&leaf, vec![]),
+			node(&leaf, vec![]),
+		]);
+
+		let referrers = |index: usize| {
+			walker
+				.try_node(index)
+				.unwrap()
+				.referrers()
+				.map(|(node, field, element)| (node.index(), field.to_string(), element))
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(referrers(0), []);
+		assert_eq!(
+			referrers(1),
+			[(0, "single".into(), None), (0, "many".into(), Some(1))]
+		);
+		assert_eq!(referrers(2), [(0, "many".into(), Some(0))]);
+	}
+
+	#[test]
+	fn lookup() {
+		let base = definition(
+			"base",
+			None,
+			vec![
+				("stored", FieldKind::Integer),
+				("omitted", FieldKind::Integer),
+			],
+		);
+		let walker = walker(vec![node(&base, vec![Some(Value::I32(1)), None])]);
+
+		assert!(matches!(
+			walker.lookup("stored"),
+			FieldLookup::Present(Value::I32(1))
+		));
+		assert!(matches!(walker.lookup("omitted"), FieldLookup::NotStored));
+		assert!(matches!(walker.lookup("missing"), FieldLookup::NoSuchField));
+	}
+
+	#[test]
+	fn field_or_default() {
+		let base = definition(
+			"base",
+			None,
+			vec![
+				("integer", FieldKind::Integer),
+				("array", FieldKind::Array(FieldKind::Float.into(), 4)),
+				("struct", FieldKind::Struct("other".into())),
+			],
+		);
+		let walker = walker(vec![node(&base, vec![None, None, None])]);
+
+		assert!(matches!(
+			walker.field_or_default("integer").as_deref(),
+			Some(Value::I32(0))
+		));
+		assert!(matches!(
+			walker.field_or_default("array").as_deref(),
+			Some(Value::Vector(values)) if values.len() == 4
+		));
+		assert!(walker.field_or_default("struct").is_none());
+		assert!(walker.field_or_default("missing").is_none());
+
+		assert_eq!(
+			walker.get::<NodeWalker>("struct").unwrap_err().to_string(),
+			"Invalid: base.struct: Field was not stored, and has no default value."
+		);
+		assert_eq!(
+			walker.get::<i32>("missing").unwrap_err().to_string(),
+			"Invalid: base.missing: Missing field."
+		);
+	}
+
+	#[test]
+	fn checked_navigation() {
+		let base = definition("base", None, vec![]);
+		let walker = walker(vec![node(&base, vec![]), node(&base, vec![])]);
+
+		assert_eq!(walker.node(1).map(|node| node.index()), Some(1));
+		assert!(walker.node(2).is_none());
+		assert!(walker.try_node(2).is_err());
+
+		assert_eq!(
+			walker
+				.resolve(&Value::Node(1))
+				.unwrap()
+				.map(|node| node.index()),
+			Some(1)
+		);
+		assert!(walker.resolve(&Value::Node(NULL_NODE)).unwrap().is_none());
+		assert!(walker.resolve(&Value::Node(2)).is_err());
+		assert!(walker.resolve(&Value::I32(1)).is_err());
+	}
+
+	#[test]
+	fn thread_safe() {
+		fn assert_send_sync<T: Send + Sync>() {}
+		assert_send_sync::<NodeWalker>();
+	}
+}